@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+
+use crate::error::Error;
+use crate::utils::read_file;
+
+/// Accepted answers keyed by day and part, read from a small TOML subset:
+///
+/// ```toml
+/// [day_1]
+/// part1 = 1018
+/// part2 = "5815"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, u8), u64>,
+}
+
+impl Answers {
+    pub fn read(path: &str) -> Result<Self, Error> {
+        Answers::parse(&read_file(path)?).map_err(|err| err.in_file(path))
+    }
+
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut answers = BTreeMap::new();
+        let mut day = None;
+
+        for (line_idx, raw_line) in input.lines().enumerate() {
+            let line_no = line_idx + 1;
+            // Everything after '#' is a comment
+            let line = raw_line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[') {
+                let name = table.strip_suffix(']')
+                    .ok_or_else(|| Error::parse_at(line_no, raw_line, line, "Unclosed table header"))?;
+                let number = name.trim().strip_prefix("day_")
+                    .and_then(|number| number.parse::<u8>().ok())
+                    .ok_or_else(|| Error::parse_at(line_no, raw_line, name, "Expected a table named [day_<N>]"))?;
+                day = Some(number);
+                continue;
+            }
+
+            let (key, value) = line.split_once('=')
+                .ok_or_else(|| Error::parse_at(line_no, raw_line, line, "Expected `part<P> = <answer>`"))?;
+            let (key, value) = (key.trim(), value.trim());
+
+            let day = day.ok_or_else(|| Error::parse_at(line_no, raw_line, key, "Answer outside of a [day_<N>] table"))?;
+            let part = match key {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(Error::parse_at(line_no, raw_line, key, "Expected part1 or part2")),
+            };
+
+            let unquoted = value.strip_prefix('"').and_then(|value| value.strip_suffix('"')).unwrap_or(value);
+            let answer = unquoted.parse::<u64>()
+                .map_err(|err| Error::parse_at(line_no, raw_line, value, format!("Invalid answer: {err}")))?;
+
+            if answers.insert((day, part), answer).is_some() {
+                return Err(Error::parse_at(line_no, raw_line, key, format!("Duplicate answer for day {day} {key}")));
+            }
+        }
+
+        Ok(Answers { answers })
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<u64> {
+        self.answers.get(&(day, part)).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_answers() {
+        let answers = Answers::parse("\
+# Accepted answers
+[day_1]
+part1 = 1018
+part2 = \"5815\" # quoted
+
+[day_12]
+part1 = 490
+").unwrap();
+
+        assert_eq!(answers.len(), 3);
+        assert_eq!(answers.get(1, 1), Some(1018));
+        assert_eq!(answers.get(1, 2), Some(5815));
+        assert_eq!(answers.get(12, 1), Some(490));
+        assert_eq!(answers.get(12, 2), None);
+    }
+
+    #[test]
+    fn rejects_malformed_answers() {
+        for input in ["part1 = 1", "[day_x]", "[day_1\npart1 = 1", "[day_1]\npart3 = 1", "[day_1]\npart1 = abc", "[day_1]\npart1 = 1\npart1 = 2"] {
+            assert!(matches!(Answers::parse(input), Err(Error::Parse(_))), "{input}");
+        }
+    }
+}
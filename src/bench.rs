@@ -0,0 +1,149 @@
+use std::fmt::Write;
+use std::time::{ Duration, Instant };
+
+use crate::error::Error;
+use crate::report::json_string;
+use crate::solution::Solution;
+
+/// Minimum, median and maximum of a set of timing samples
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarise `samples`, `None` when there are none.
+    /// The median of an even number of samples is the mean of the middle two.
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+
+        let mid = sorted.len() / 2;
+        let median = match sorted.len() {
+            0 => return None,
+            len if len % 2 == 1 => sorted[mid],
+            _ => (sorted[mid - 1] + sorted[mid]) / 2,
+        };
+
+        Some(Stats { min: sorted[0], median, max: sorted[sorted.len() - 1] })
+    }
+}
+
+/// Timings of one part of one day over repeated runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartBench {
+    pub day: u8,
+    pub part: u8,
+    pub title: &'static str,
+    pub runs: u32,
+    pub answer: u64,
+    /// Time spent in [`Solution::parse`]
+    pub parse: Stats,
+    /// Time spent solving the part, minus the parse time of the same run
+    pub solve: Stats,
+}
+
+/// Run `part` of `solution` on `input` `runs` times, stopping at the first error
+pub fn bench_part(solution: &dyn Solution, part: u8, input: &str, runs: u32) -> Result<PartBench, Error> {
+    let runs = runs.max(1);
+    let mut parse_samples = Vec::with_capacity(runs as usize);
+    let mut solve_samples = Vec::with_capacity(runs as usize);
+    let mut answer = 0;
+
+    for _ in 0..runs {
+        let start = Instant::now();
+        solution.parse(input)?;
+        let parsed = start.elapsed();
+
+        let start = Instant::now();
+        answer = solution.solve(part, input)?;
+        let solved = start.elapsed();
+
+        // The solvers parse their own input, so subtract this run's parse time
+        parse_samples.push(parsed);
+        solve_samples.push(solved.saturating_sub(parsed));
+    }
+
+    Ok(PartBench {
+        day: solution.day(),
+        part,
+        title: solution.title(),
+        runs,
+        answer,
+        // `runs` is at least one, so there is always a sample
+        parse: Stats::from_samples(&parse_samples).unwrap_or_default(),
+        solve: Stats::from_samples(&solve_samples).unwrap_or_default(),
+    })
+}
+
+/// Render benchmark results as a JSON document, durations in nanoseconds
+pub fn to_json(results: &[PartBench]) -> String {
+    let mut json = String::from("{\n  \"results\": [");
+
+    for (idx, result) in results.iter().enumerate() {
+        let separator = if idx == 0 { "" } else { "," };
+        let _ = write!(
+            json,
+            "{separator}\n    {{ \"day\": {}, \"part\": {}, \"title\": {}, \"runs\": {}, \"answer\": {}, \"parse_ns\": {}, \"solve_ns\": {} }}",
+            result.day,
+            result.part,
+            json_string(result.title),
+            result.runs,
+            result.answer,
+            stats_json(&result.parse),
+            stats_json(&result.solve),
+        );
+    }
+
+    if !results.is_empty() {
+        json.push_str("\n  ");
+    }
+    json.push_str("]\n}\n");
+    json
+}
+
+fn stats_json(stats: &Stats) -> String {
+    format!(
+        "{{ \"min\": {}, \"median\": {}, \"max\": {} }}",
+        stats.min.as_nanos(),
+        stats.median.as_nanos(),
+        stats.max.as_nanos()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: &[u64]) -> Vec<Duration> {
+        millis.iter().map(|&millis| Duration::from_millis(millis)).collect()
+    }
+
+    #[test]
+    fn stats_of_samples() {
+        assert_eq!(Stats::from_samples(&[]), None);
+        assert_eq!(
+            Stats::from_samples(&ms(&[5, 1, 3])),
+            Some(Stats { min: Duration::from_millis(1), median: Duration::from_millis(3), max: Duration::from_millis(5) })
+        );
+        assert_eq!(Stats::from_samples(&ms(&[4, 1, 2, 9])).map(|stats| stats.median), Some(Duration::from_millis(3)));
+    }
+
+    #[test]
+    fn renders_json() {
+        let stats = Stats { min: Duration::from_nanos(1), median: Duration::from_nanos(2), max: Duration::from_nanos(3) };
+        let result = PartBench { day: 7, part: 2, title: "Laboratories", runs: 3, answer: 40, parse: stats, solve: stats };
+
+        assert_eq!(to_json(&[]), "{\n  \"results\": []\n}\n");
+        assert_eq!(to_json(&[result]), "\
+{
+  \"results\": [
+    { \"day\": 7, \"part\": 2, \"title\": \"Laboratories\", \"runs\": 3, \"answer\": 40, \
+\"parse_ns\": { \"min\": 1, \"median\": 2, \"max\": 3 }, \"solve_ns\": { \"min\": 1, \"median\": 2, \"max\": 3 } }
+  ]
+}
+");
+    }
+}
@@ -0,0 +1,412 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use aoc2025::day_4::FrameFormat;
+
+pub const USAGE: &str = "\
+Usage:
+    aoc2025 run --day <N> [--part <1|2>] [--input <PATH>...] [--format <FORMAT>] [--explain]
+    aoc2025 run --day <N> --part <1|2> [--input <PATH>] --frames <DIR> [--frame-format <FORMAT>]
+    aoc2025 run --all [--part <1|2>] [--format <FORMAT>]
+    aoc2025 verify [--day <N>] [--part <1|2>] [--answers <PATH>]
+    aoc2025 bench [--day <N>] [--part <1|2>] [--input <PATH>] [--runs <N>] [--json <PATH>]
+    aoc2025 submit --day <N> --part <1|2> [--input <PATH>] [--history <PATH>]
+
+Options:
+    -d, --day <N>         Day to run
+    -p, --part <P>        Part to run (1 or 2), both parts when omitted
+    -i, --input <PATH>    Puzzle input, defaults to data/day_<N>.txt. `-` reads stdin;
+                          run also takes several files or directories
+    -a, --all             Run every day
+    -f, --format <FORMAT> Output of run: text (default), json or csv
+        --explain         Also print how each answer comes about, for days that support it
+        --frames <DIR>    Also write how the answer comes about as one frame per step into DIR,
+                          for days that support it
+        --frame-format <FORMAT>
+                          Frames as text (default) or pgm images, 4 pixels per cell
+        --answers <PATH>  Accepted answers, defaults to data/answers.toml
+        --runs <N>        Benchmark runs per part, defaults to 10
+        --json <PATH>     Also write benchmark results as JSON
+        --history <PATH>  Submission history, defaults to data/submissions.tsv
+    -h, --help            Print this help";
+
+pub const DEFAULT_ANSWERS: &str = "data/answers.toml";
+pub const DEFAULT_RUNS: u32 = 10;
+pub const DEFAULT_HISTORY: &str = "data/submissions.tsv";
+/// Pixels per grid cell of PGM frames
+pub const FRAME_SCALE: usize = 4;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(RunArgs),
+    Verify(VerifyArgs),
+    Bench(BenchArgs),
+    Submit(SubmitArgs),
+    Help,
+}
+
+/// How `run` prints its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RunArgs {
+    /// Day to run, every registered day when `None`
+    pub day: Option<u8>,
+    pub parts: Vec<u8>,
+    /// Input files or directories, the day's default input when empty
+    pub inputs: Vec<String>,
+    pub format: Format,
+    /// Print the day's breakdown of each answer after the answers
+    pub explain: bool,
+    /// Write the day's frames of the answer, if anywhere
+    pub frames: Option<FrameExport>,
+}
+
+/// Where and how `run` writes frames
+#[derive(Debug, PartialEq)]
+pub struct FrameExport {
+    pub dir: String,
+    pub format: FrameFormat,
+}
+
+/// Compare solver output against the accepted answers
+#[derive(Debug, PartialEq)]
+pub struct VerifyArgs {
+    /// Day to verify, every registered day when `None`
+    pub day: Option<u8>,
+    pub parts: Vec<u8>,
+    pub answers: String,
+}
+
+/// Time parsing and solving over repeated runs
+#[derive(Debug, PartialEq)]
+pub struct BenchArgs {
+    /// Day to benchmark, every registered day when `None`
+    pub day: Option<u8>,
+    pub parts: Vec<u8>,
+    pub input: Option<String>,
+    pub runs: u32,
+    /// Where to write the results as JSON, if anywhere
+    pub json: Option<String>,
+}
+
+/// Send a solver's answer to the puzzle server
+#[derive(Debug, PartialEq)]
+pub struct SubmitArgs {
+    pub day: u8,
+    pub part: u8,
+    pub input: Option<String>,
+    pub history: String,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter().peekable();
+
+    let command = match args.next().map(String::as_str) {
+        Some(command @ ("run" | "verify" | "bench" | "submit")) => command,
+        Some("-h") | Some("--help") | Some("help") | None => return Ok(Command::Help),
+        Some(other) => return Err(format!("Unknown command '{other}'")),
+    };
+    let verify = command == "verify";
+    let bench = command == "bench";
+    let submit = command == "submit";
+
+    let mut day = None;
+    let mut part = None;
+    let mut inputs: Vec<String> = Vec::new();
+    let mut answers = None;
+    let mut runs = None;
+    let mut json = None;
+    let mut history = None;
+    let mut format = Format::Text;
+    let mut all = false;
+    let mut explain = false;
+    let mut frames = None;
+    let mut frame_format = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" | "--day" => day = Some(parse_number(arg, args.next(), 1, u8::MAX)?),
+            "-p" | "--part" => part = Some(parse_number(arg, args.next(), 1, 2)?),
+            "-i" | "--input" => {
+                let path = args.next().ok_or_else(|| format!("Missing value for {arg}"))?;
+                inputs.push(path.clone());
+                // Further paths up to the next flag belong to the same --input
+                while let Some(path) = args.next_if(|next| next.as_str() == "-" || !next.starts_with('-')) {
+                    inputs.push(path.clone());
+                }
+            },
+            "--answers" if verify => {
+                let path = args.next().ok_or_else(|| format!("Missing value for {arg}"))?;
+                answers = Some(path.clone());
+            },
+            "--runs" if bench => runs = Some(parse_number(arg, args.next(), 1, u32::MAX)?),
+            "--json" if bench => {
+                let path = args.next().ok_or_else(|| format!("Missing value for {arg}"))?;
+                json = Some(path.clone());
+            },
+            "--history" if submit => {
+                let path = args.next().ok_or_else(|| format!("Missing value for {arg}"))?;
+                history = Some(path.clone());
+            },
+            "-f" | "--format" if command == "run" => {
+                format = match args.next().map(String::as_str) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some("csv") => Format::Csv,
+                    Some(other) => return Err(format!("Unknown format '{other}', expected text, json or csv")),
+                    None => return Err(format!("Missing value for {arg}")),
+                };
+            },
+            "--explain" if command == "run" => explain = true,
+            "--frames" if command == "run" => {
+                let dir = args.next().ok_or_else(|| format!("Missing value for {arg}"))?;
+                frames = Some(dir.clone());
+            },
+            "--frame-format" if command == "run" => {
+                frame_format = match args.next().map(String::as_str) {
+                    Some("text") => Some(FrameFormat::Text),
+                    Some("pgm") => Some(FrameFormat::Pgm { scale: FRAME_SCALE }),
+                    Some(other) => return Err(format!("Unknown frame format '{other}', expected text or pgm")),
+                    None => return Err(format!("Missing value for {arg}")),
+                };
+            },
+            "-a" | "--all" if !submit => all = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            other => return Err(format!("Unknown argument '{other}'")),
+        }
+    }
+
+    if inputs.len() > 1 && command != "run" {
+        return Err(format!("{command} takes a single --input"));
+    }
+    let input = if command == "run" { None } else { inputs.first().cloned() };
+
+    if submit {
+        let day = day.ok_or("submit requires --day")?;
+        let part = part.ok_or("submit requires --part")?;
+        let history = history.unwrap_or_else(|| DEFAULT_HISTORY.to_string());
+        return Ok(Command::Submit(SubmitArgs { day, part, input, history }));
+    }
+
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![1, 2],
+    };
+
+    if verify {
+        if day.is_some() && all {
+            return Err("--day and --all cannot be combined".into());
+        }
+        if !inputs.is_empty() {
+            return Err("verify always uses the inputs under data/".into());
+        }
+
+        let answers = answers.unwrap_or_else(|| DEFAULT_ANSWERS.to_string());
+        return Ok(Command::Verify(VerifyArgs { day, parts, answers }));
+    }
+
+    if bench {
+        if day.is_some() && all {
+            return Err("--day and --all cannot be combined".into());
+        }
+        if day.is_none() && input.is_some() {
+            return Err("--input requires --day".into());
+        }
+
+        let runs = runs.unwrap_or(DEFAULT_RUNS);
+        return Ok(Command::Bench(BenchArgs { day, parts, input, runs, json }));
+    }
+
+    match (day, all) {
+        (Some(_), true) => return Err("--day and --all cannot be combined".into()),
+        (None, false) => return Err("Either --day or --all is required".into()),
+        _ => {},
+    }
+
+    if all && !inputs.is_empty() {
+        return Err("--input cannot be combined with --all".into());
+    }
+
+    if explain && (all || format != Format::Text) {
+        return Err("--explain needs --day and text output".into());
+    }
+
+    if frame_format.is_some() && frames.is_none() {
+        return Err("--frame-format needs --frames".into());
+    }
+    if frames.is_some() && (all || part.is_none() || inputs.len() > 1 || format != Format::Text) {
+        return Err("--frames needs --day, --part, a single input and text output".into());
+    }
+    let frames = frames.map(|dir| FrameExport { dir, format: frame_format.unwrap_or(FrameFormat::Text) });
+
+    Ok(Command::Run(RunArgs { day, parts, inputs, format, explain, frames }))
+}
+
+fn parse_number<T>(flag: &str, value: Option<&String>, min: T, max: T) -> Result<T, String>
+where
+    T: FromStr + PartialOrd + Display,
+{
+    let value = value.ok_or_else(|| format!("Missing value for {flag}"))?;
+
+    let number = value.parse::<T>()
+        .map_err(|_| format!("Invalid value '{value}' for {flag}"))?;
+
+    if number < min || number > max {
+        return Err(format!("Value for {flag} must be between {min} and {max}, got {number}"));
+    }
+
+    Ok(number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, String> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn run_single_part() {
+        assert_eq!(
+            parse("run --day 7 --part 1 --input example.txt"),
+            Ok(Command::Run(RunArgs {
+                day: Some(7),
+                parts: vec![1],
+                inputs: vec!["example.txt".into()],
+                format: Format::Text,
+                explain: false,
+                frames: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn run_several_inputs() {
+        assert_eq!(
+            parse("run --day 7 --input a.txt examples/ - --part 2 -i b.txt"),
+            Ok(Command::Run(RunArgs {
+                day: Some(7),
+                parts: vec![2],
+                inputs: vec!["a.txt".into(), "examples/".into(), "-".into(), "b.txt".into()],
+                format: Format::Text,
+                explain: false,
+                frames: None,
+            }))
+        );
+        assert!(parse("bench --day 7 --input a.txt b.txt").is_err());
+    }
+
+    #[test]
+    fn run_all_parts() {
+        assert_eq!(
+            parse("run -a --format csv"),
+            Ok(Command::Run(RunArgs { day: None, parts: vec![1, 2], inputs: vec![], format: Format::Csv, explain: false, frames: None }))
+        );
+        assert_eq!(
+            parse("run -d 3 -p 2 --explain"),
+            Ok(Command::Run(RunArgs { day: Some(3), parts: vec![2], inputs: vec![], format: Format::Text, explain: true, frames: None }))
+        );
+    }
+
+    #[test]
+    fn run_with_frames() {
+        assert_eq!(
+            parse("run -d 4 -p 2 --frames frames/ --frame-format pgm"),
+            Ok(Command::Run(RunArgs {
+                day: Some(4),
+                parts: vec![2],
+                inputs: vec![],
+                format: Format::Text,
+                explain: false,
+                frames: Some(FrameExport { dir: "frames/".into(), format: FrameFormat::Pgm { scale: FRAME_SCALE } }),
+            }))
+        );
+        assert!(matches!(
+            parse("run -d 4 -p 2 --frames frames/"),
+            Ok(Command::Run(RunArgs { frames: Some(FrameExport { format: FrameFormat::Text, .. }), .. }))
+        ));
+        assert!(parse("run -d 4 --frames frames/").is_err());
+        assert!(parse("run -a -p 2 --frames frames/").is_err());
+        assert!(parse("run -d 4 -p 2 --frames frames/ --format json").is_err());
+        assert!(parse("run -d 4 -p 2 --frames frames/ --input a.txt b.txt").is_err());
+        assert!(parse("run -d 4 -p 2 --frame-format pgm").is_err());
+        assert!(parse("run -d 4 -p 2 --frames frames/ --frame-format gif").is_err());
+        assert!(parse("bench -d 4 -p 2 --frames frames/").is_err());
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert!(parse("run").is_err());
+        assert!(parse("run --day 0").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+        assert!(parse("run --day 1 --all").is_err());
+        assert!(parse("run --all --input x.txt").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("solve --day 1").is_err());
+        assert!(parse("run --day 1 --format xml").is_err());
+        assert!(parse("bench --format json").is_err());
+        assert!(parse("run --all --explain").is_err());
+        assert!(parse("run --day 3 --explain --format json").is_err());
+        assert!(parse("verify --explain").is_err());
+    }
+
+    #[test]
+    fn verify_defaults_to_every_day() {
+        assert_eq!(
+            parse("verify"),
+            Ok(Command::Verify(VerifyArgs { day: None, parts: vec![1, 2], answers: DEFAULT_ANSWERS.into() }))
+        );
+        assert_eq!(
+            parse("verify --day 3 --part 2 --answers mine.toml"),
+            Ok(Command::Verify(VerifyArgs { day: Some(3), parts: vec![2], answers: "mine.toml".into() }))
+        );
+        assert!(parse("verify --input x.txt").is_err());
+        assert!(parse("run --day 1 --answers mine.toml").is_err());
+    }
+
+    #[test]
+    fn bench_options() {
+        assert_eq!(
+            parse("bench"),
+            Ok(Command::Bench(BenchArgs { day: None, parts: vec![1, 2], input: None, runs: DEFAULT_RUNS, json: None }))
+        );
+        assert_eq!(
+            parse("bench --day 2 --part 1 --runs 50 --json bench.json"),
+            Ok(Command::Bench(BenchArgs {
+                day: Some(2),
+                parts: vec![1],
+                input: None,
+                runs: 50,
+                json: Some("bench.json".into()),
+            }))
+        );
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("bench --input x.txt").is_err());
+        assert!(parse("run --day 1 --runs 5").is_err());
+    }
+
+    #[test]
+    fn submit_requires_day_and_part() {
+        assert_eq!(
+            parse("submit --day 4 --part 2"),
+            Ok(Command::Submit(SubmitArgs { day: 4, part: 2, input: None, history: DEFAULT_HISTORY.into() }))
+        );
+        assert!(parse("submit --day 4").is_err());
+        assert!(parse("submit --part 1").is_err());
+        assert!(parse("submit --all --part 1").is_err());
+    }
+
+    #[test]
+    fn help() {
+        assert_eq!(parse(""), Ok(Command::Help));
+        assert_eq!(parse("run --help"), Ok(Command::Help));
+    }
+}
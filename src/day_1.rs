@@ -1,97 +1,405 @@
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 
-const TASK_VERSION: u8 = 2;
+/// Position the dial points at before the first rotation
+pub const INITIAL_POSITION: u64 = 50;
+/// Number of positions on the dial, 0 to 99
+pub const LOCK_SIZE: u64 = 100;
 
-const INITIAL_POSITION: u8 = 50;
-const LOCK_SIZE: i32 = 100;
+pub struct Puzzle;
 
-pub fn safe(path: &str) -> Result<u32, String> {
-    match TASK_VERSION {
-        1 => safe_01(path),
-        2 => safe_02(path),
-        _ => Err("Invalid task version!".into()),
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        1
+    }
+
+    fn title(&self) -> &'static str {
+        "Secret Entrance"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        parse_rotations(input).map(drop)
     }
 }
 
-pub fn safe_01(path: &str) -> Result<u32, String> {
-    let puzzle = read_file(path);
+/// Solve part one for the puzzle input file at `path`
+pub fn safe_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-    let combinations = parse_puzzle(&puzzle)?;
-    
-    let mut curr_position = INITIAL_POSITION as i32;
-    let mut result = 0;
-    for comb in combinations {
-        if comb.0 == 'L' {
-            curr_position = (curr_position - comb.1).rem_euclid(LOCK_SIZE);
-        }
-        else if comb.0 == 'R' {
-            curr_position = (curr_position + comb.1).rem_euclid(LOCK_SIZE);
-        }
-        else {
-            return Err("Wrong direction!".into());
-        }
+/// Solve part two for the puzzle input file at `path`
+pub fn safe_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-        if curr_position == 0 {
-            result += 1;
-        }
+/// Solve part one for the puzzle input text
+pub fn solve_01(puzzle: &str) -> Result<u64, Error> {
+    let rotations = parse_rotations(puzzle)?;
+
+    let landed = Dial::default()
+        .events(rotations)
+        .filter(|event| event.landed_on_zero)
+        .count();
+
+    Ok(landed as u64)
+}
+
+/// Solve part two for the puzzle input text
+pub fn solve_02(puzzle: &str) -> Result<u64, Error> {
+    let rotations = parse_rotations(puzzle)?;
+
+    let clicks_on_zero = Dial::default()
+        .events(rotations)
+        .map(|event| event.zero_clicks())
+        .sum();
+
+    Ok(clicks_on_zero)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Towards lower numbers
+    Left,
+    /// Towards higher numbers
+    Right,
+}
+
+/// Turn of the dial by a number of clicks, e.g. `L68`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rotation {
+    pub direction: Direction,
+    pub clicks: u64,
+}
+
+/// What happened during a single rotation of the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RotationEvent {
+    pub rotation: Rotation,
+    /// Position before the rotation
+    pub start: u64,
+    /// Position after the rotation
+    pub end: u64,
+    /// Times the dial passed 0 without stopping there
+    pub zero_crossings: u64,
+    pub landed_on_zero: bool,
+}
+
+impl RotationEvent {
+    /// Clicks that left the dial pointing at 0, the final click included
+    pub fn zero_clicks(&self) -> u64 {
+        self.zero_crossings + (self.landed_on_zero && self.rotation.clicks > 0) as u64
     }
+}
 
-    Ok(result)
+/// Circular dial numbered `0..size` and the position it points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dial {
+    size: u64,
+    position: u64,
 }
 
-pub fn safe_02(path: &str) -> Result<u32, String> {
-    let puzzle = read_file(path);
+impl Default for Dial {
+    /// The dial from the puzzle, 100 positions starting at 50
+    fn default() -> Self {
+        Dial { size: LOCK_SIZE, position: INITIAL_POSITION }
+    }
+}
 
-    let combinations = parse_puzzle(&puzzle)?;
-    
-    let mut curr_position = INITIAL_POSITION as i32;
-    let mut result = 0;
-    for comb in combinations {
-        let turns = (comb.1 / LOCK_SIZE) as u32;
-        result += turns;
-        
-        if comb.0 == 'L' {
-            let tmp = comb.1.rem_euclid(LOCK_SIZE);
-            let sub = curr_position - tmp;
-            if sub < 0 && curr_position != 0 {
-                result += 1;
-            }
+impl Dial {
+    pub fn new(size: u64, position: u64) -> Result<Self, Error> {
+        check_position(size, position)?;
 
-            curr_position = (curr_position - comb.1).rem_euclid(LOCK_SIZE);
-        }
-        else if comb.0 == 'R' {
-            let tmp = comb.1.rem_euclid(LOCK_SIZE);
-            let sub = curr_position + tmp;
-            if sub > LOCK_SIZE && curr_position != 0 {
-                result += 1;
-            }
+        Ok(Dial { size, position })
+    }
 
-            curr_position = (curr_position + comb.1).rem_euclid(LOCK_SIZE);
-        }
-        else {
-            return Err("Wrong direction!".into());
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Turn the dial and report what happened on the way
+    pub fn rotate(&mut self, rotation: Rotation) -> RotationEvent {
+        let start = self.position;
+        let remainder = rotation.clicks % self.size;
+
+        // Written so that no intermediate value exceeds `size`, which may be as large as u64::MAX
+        let end = match rotation.direction {
+            Direction::Left if remainder > start => self.size - (remainder - start),
+            Direction::Left => start - remainder,
+            Direction::Right if remainder >= self.size - start => remainder - (self.size - start),
+            Direction::Right => start + remainder,
+        };
+        let landed_on_zero = end == 0;
+
+        self.position = end;
+
+        RotationEvent {
+            rotation,
+            start,
+            end,
+            // A rotation by 0 clicks "lands" on 0 without a final click
+            zero_crossings: count_zero_clicks(self.size, start, rotation) - (landed_on_zero && rotation.clicks > 0) as u64,
+            landed_on_zero,
         }
+    }
 
-        if curr_position == 0 {
-            result += 1;
+    /// Apply `rotations` one by one, yielding an event for each
+    pub fn events<I: IntoIterator<Item = Rotation>>(&mut self, rotations: I) -> Events<'_, I::IntoIter> {
+        Events { dial: self, rotations: rotations.into_iter() }
+    }
+}
+
+/// Number of clicks that leave a dial of `size` positions pointing at 0
+/// while turning it by `rotation` from `start`, the final click included.
+/// Fails if `start` is not a position on such a dial.
+pub fn zero_clicks(size: u64, start: u64, rotation: Rotation) -> Result<u64, Error> {
+    check_position(size, start)?;
+
+    Ok(count_zero_clicks(size, start, rotation))
+}
+
+/// [`zero_clicks`] for a `start` known to be on the dial.
+///
+/// Turning right, click `i` points at `start + i`, which is 0 whenever it is a multiple of `size`.
+/// The multiples of `size` in `start + 1..=start + clicks` are counted by `(start + clicks) / size`,
+/// as `start < size` contributes none below `start + 1`.
+/// Turning left from `start` mirrors turning right from `size - start`, with 0 mirroring onto itself,
+/// so the same count applies to the mirrored start.
+///
+/// `(start + clicks) / size` is evaluated as `clicks / size` full turns plus one more multiple
+/// if the leftover clicks reach past `size - start`, so it cannot overflow.
+fn count_zero_clicks(size: u64, start: u64, rotation: Rotation) -> u64 {
+    let start = match rotation.direction {
+        Direction::Right => start,
+        Direction::Left => (size - start) % size,
+    };
+
+    rotation.clicks / size + (rotation.clicks % size >= size - start) as u64
+}
+
+/// Reference for [`Dial::rotate`]: turn the dial one click at a time,
+/// returning the end position and how many clicks pointed at 0
+pub fn simulate_clicks(size: u64, start: u64, rotation: Rotation) -> Result<(u64, u64), Error> {
+    check_position(size, start)?;
+
+    let mut position = start;
+    let mut zeros = 0;
+
+    for _ in 0..rotation.clicks {
+        position = match rotation.direction {
+            Direction::Left if position == 0 => size - 1,
+            Direction::Left => position - 1,
+            Direction::Right if position == size - 1 => 0,
+            Direction::Right => position + 1,
+        };
+        if position == 0 {
+            zeros += 1;
         }
     }
 
-    Ok(result)
+    Ok((position, zeros))
 }
 
+/// Reject dials without positions and starts beyond their last position
+fn check_position(size: u64, position: u64) -> Result<(), Error> {
+    if size == 0 {
+        return Err(Error::InvalidInput("Dial needs at least one position".into()));
+    }
+    if position >= size {
+        return Err(Error::InvalidInput(format!("Start position {position} is not on a dial of size {size}")));
+    }
+
+    Ok(())
+}
+
+/// Iterator over the events of a sequence of rotations, see [`Dial::events`]
+pub struct Events<'a, I> {
+    dial: &'a mut Dial,
+    rotations: I,
+}
+
+impl<I: Iterator<Item = Rotation>> Iterator for Events<'_, I> {
+    type Item = RotationEvent;
+
+    fn next(&mut self) -> Option<RotationEvent> {
+        self.rotations.next().map(|rotation| self.dial.rotate(rotation))
+    }
+}
 
 /// Each line containes Left or Right lock turns and positive integer from 0 to 99: L10, R5, L0, R99
-fn parse_puzzle(puzzle: &str) -> Result<Vec<(char, i32)>, String> {
-    let mut turns: Vec<(char, i32)> = Vec::new();
+pub fn parse_rotations(puzzle: &str) -> Result<Vec<Rotation>, Error> {
+    let mut rotations = Vec::new();
 
-    for line in puzzle.lines() {
-        let (dir, str_value) = line.split_at(1);
+    for (line_idx, line) in puzzle.lines().enumerate() {
+        let line_no = line_idx + 1;
 
-        let value = str_value.parse::<i32>().map_err(|err| format!("Wrong lock combination!: {err}"))?;
+        let mut chars = line.chars();
+        let direction = match chars.next() {
+            Some('L') => Direction::Left,
+            Some('R') => Direction::Right,
+            Some(dir) => return Err(Error::parse(line_no, line, 1, format!("Wrong direction '{dir}'!"))),
+            None => return Err(Error::parse(line_no, line, 1, "Cannot get direction!")),
+        };
 
-        turns.push((dir.chars().next().ok_or("Cannot get direction!")?, value));
+        let str_value = chars.as_str();
+        let clicks = str_value.parse::<u32>()
+            .map_err(|err| Error::parse_at(line_no, line, str_value, format!("Wrong lock combination!: {err}")))?;
+
+        rotations.push(Rotation { direction, clicks: clicks as u64 });
+    }
+
+    Ok(rotations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Rng;
+
+    const EXAMPLE: &str = "\
+L68
+L30
+R48
+L5
+R60
+L55
+L1
+L99
+R14
+L82
+";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 3);
     }
 
-    Ok(turns)
-}
\ No newline at end of file
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 6);
+    }
+
+    #[test]
+    fn part_2_counts_full_turns() {
+        // 50 -> 50 passing 0 ten times
+        assert_eq!(solve_02("R1000").unwrap(), 10);
+    }
+
+    #[test]
+    fn traces_every_rotation() {
+        let rotations = parse_rotations(EXAMPLE).unwrap();
+        let events: Vec<RotationEvent> = Dial::default().events(rotations).collect();
+
+        let positions: Vec<(u64, u64)> = events.iter().map(|event| (event.start, event.end)).collect();
+        assert_eq!(positions[..4], [(50, 82), (82, 52), (52, 0), (0, 95)]);
+
+        let crossings: Vec<u64> = events.iter().map(|event| event.zero_crossings).collect();
+        assert_eq!(crossings, [1, 0, 0, 0, 1, 0, 0, 0, 0, 1]);
+        assert_eq!(events.iter().filter(|event| event.landed_on_zero).count(), 3);
+    }
+
+    #[test]
+    fn configurable_dial() {
+        let mut dial = Dial::new(10, 3).unwrap();
+        let event = dial.rotate(Rotation { direction: Direction::Left, clicks: 25 });
+
+        assert_eq!((event.start, event.end, event.zero_crossings, event.landed_on_zero), (3, 8, 3, false));
+        assert_eq!(dial.position(), 8);
+        assert!(matches!(Dial::new(10, 10), Err(Error::InvalidInput(_))));
+        assert!(matches!(Dial::new(0, 0), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn huge_dials_and_rotations_do_not_overflow() {
+        let left = |clicks| Rotation { direction: Direction::Left, clicks };
+        let right = |clicks| Rotation { direction: Direction::Right, clicks };
+
+        let mut dial = Dial::new(u64::MAX, 1).unwrap();
+        let event = dial.rotate(left(u64::MAX));
+        assert_eq!((event.end, event.zero_crossings, event.landed_on_zero), (1, 1, false));
+        let event = dial.rotate(right(u64::MAX - 1));
+        assert_eq!((event.end, event.zero_crossings, event.landed_on_zero), (0, 0, true));
+        let event = dial.rotate(left(1));
+        assert_eq!((event.end, event.zero_crossings, event.landed_on_zero), (u64::MAX - 1, 0, false));
+
+        let event = Dial::default().rotate(right(u64::MAX));
+        assert_eq!((event.end, event.zero_crossings), (65, u64::MAX / 100));
+        let event = Dial::default().rotate(left(u64::MAX));
+        assert_eq!((event.end, event.zero_crossings), (35, u64::MAX / 100));
+    }
+
+    #[test]
+    fn zero_clicks_edge_cases() {
+        let right = |clicks| Rotation { direction: Direction::Right, clicks };
+        let left = |clicks| Rotation { direction: Direction::Left, clicks };
+
+        // Starting on 0 does not count, ending on it does
+        assert_eq!(zero_clicks(100, 0, right(100)).unwrap(), 1);
+        assert_eq!(zero_clicks(100, 0, left(100)).unwrap(), 1);
+        assert_eq!(zero_clicks(100, 0, left(99)).unwrap(), 0);
+        assert_eq!(zero_clicks(100, 5, left(5)).unwrap(), 1);
+        assert_eq!(zero_clicks(100, 5, left(105)).unwrap(), 2);
+        assert_eq!(zero_clicks(100, 95, right(4)).unwrap(), 0);
+        assert_eq!(zero_clicks(1, 0, left(7)).unwrap(), 7);
+
+        // Only positions on the dial are accepted
+        assert!(matches!(zero_clicks(0, 0, right(1)), Err(Error::InvalidInput(_))));
+        assert!(matches!(zero_clicks(100, 100, left(1)), Err(Error::InvalidInput(_))));
+        assert!(matches!(simulate_clicks(0, 0, right(1)), Err(Error::InvalidInput(_))));
+        assert!(matches!(simulate_clicks(10, 12, right(1)), Err(Error::InvalidInput(_))));
+
+        // Staying on 0 lands there without a click
+        let event = Dial::new(100, 0).unwrap().rotate(left(0));
+        assert!(event.landed_on_zero);
+        assert_eq!(event.zero_clicks(), 0);
+
+        // Huge dials and rotations do not overflow
+        assert_eq!(zero_clicks(u64::MAX, 1, left(u64::MAX)).unwrap(), 1);
+        assert_eq!(zero_clicks(u64::MAX, 1, right(u64::MAX - 1)).unwrap(), 1);
+        assert_eq!(zero_clicks(100, 50, right(u64::MAX)).unwrap(), u64::MAX / 100);
+    }
+
+    #[test]
+    fn dial_matches_click_simulation() {
+        let mut rng = Rng::new(1);
+
+        for _ in 0..500 {
+            let size = rng.range(1..=120);
+            let mut dial = Dial::new(size, rng.range(0..=size - 1)).unwrap();
+
+            for _ in 0..rng.range(1..=20) {
+                let direction = if rng.range(0..=1) == 0 { Direction::Left } else { Direction::Right };
+                // Favour exact multiples of the size, where off-by-one mistakes hide
+                let clicks = match rng.range(0..=3) {
+                    0 => size * rng.range(0..=5),
+                    _ => rng.range(0..=size * 6),
+                };
+                let rotation = Rotation { direction, clicks };
+
+                let (end, zeros) = simulate_clicks(size, dial.position(), rotation).unwrap();
+                let event = dial.rotate(rotation);
+
+                assert_eq!(event.end, end, "{rotation:?} from {} on {size}", event.start);
+                assert_eq!(event.zero_clicks(), zeros, "{rotation:?} from {} on {size}", event.start);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_direction() {
+        let err = solve_01("L10\nU5").unwrap_err();
+        assert!(matches!(err, Error::Parse(ref parse) if parse.line == 2 && parse.column == 1), "{err:?}");
+    }
+}
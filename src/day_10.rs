@@ -1,73 +1,111 @@
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 
-const TASK_VERSION: u8 = 2;
-
-#[derive(Debug)]
-struct Machine {
-    n_lights: usize,
-    m_buttons: usize,
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    pub n_lights: usize,
+    pub m_buttons: usize,
     // each button is a list of indices it toggles/increments
-    buttons: Vec<Vec<usize>>,
+    pub buttons: Vec<Vec<usize>>,
     // target vector for part 1 (binary)
-    target: Vec<u8>,
+    pub target: Vec<u8>,
     // joltage requirements for part 2 (integers)
-    joltage: Vec<i64>,
+    pub joltage: Vec<i64>,
+}
+
+impl Machine {
+    pub fn new(buttons: Vec<Vec<usize>>, target: Vec<u8>, joltage: Vec<i64>) -> Self {
+        Machine {
+            n_lights: target.len(),
+            m_buttons: buttons.len(),
+            buttons,
+            target,
+            joltage,
+        }
+    }
 }
 
-pub fn buttons(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => buttons_01(path),
-        2 => buttons_02(path),
-        _ => Err("Invalid task version!".into()),
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        10
+    }
+
+    fn title(&self) -> &'static str {
+        "Factory"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
     }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        parse_input(input).map(drop)
+    }
+}
+
+/// Solve part one for the puzzle input file at `path`
+pub fn buttons_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part two for the puzzle input file at `path`
+pub fn buttons_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
 }
 
-pub fn buttons_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-    let machines = parse_input(&input);
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    let machines = parse_input(input)?;
 
     let mut total: u64 = 0;
     for m in machines {
-        match solve_machine(&m) {
-            Ok(min_presses) => total += min_presses as u64,
-            Err(e) => return Err(format!("No solution for a machine: {}", e)),
-        }
+        total += solve_machine(&m)? as u64;
     }
 
     Ok(total)
 }
 
 
-pub fn buttons_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-    let machines = parse_input(&input);
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    let machines = parse_input(input)?;
 
     let mut total: u64 = 0;
     for m in machines {
-        match solve_machine_joltage(&m) {
-            Ok(min_presses) => total += min_presses as u64,
-            Err(e) => return Err(format!("No solution for a machine: {}", e)),
-        }
+        total += solve_machine_joltage(&m)? as u64;
     }
 
     Ok(total)
 }
 
-fn parse_input(input: &str) -> Vec<Machine> {
+/// Parse one machine per line: `[.##.] (3) (1,3) (2) {3,5,4,7}`
+pub fn parse_input(input: &str) -> Result<Vec<Machine>, Error> {
     let mut out = Vec::new();
 
-    for line in input.lines() {
-        let line = line.trim();
-        if line.is_empty() { continue; }
+    for (line_idx, line) in input.lines().enumerate() {
+        let line_no = line_idx + 1;
 
         // split into tokens by whitespace
         let tokens: Vec<&str> = line.split_whitespace().collect();
         if tokens.is_empty() { continue; }
 
         // first token is [..#..]
-        let lights_tok = tokens[0];
-        let lights_inner = &lights_tok[1..lights_tok.len()-1];
-        let target: Vec<u8> = lights_inner.chars().map(|c| if c == '#' {1} else {0}).collect();
+        let lights_inner = bracketed(line_no, line, tokens[0], '[', ']')?;
+        let target: Vec<u8> = lights_inner
+            .char_indices()
+            .map(|(pos, c)| match c {
+                '#' => Ok(1),
+                '.' => Ok(0),
+                _ => Err(Error::parse_at(line_no, line, &lights_inner[pos..pos + c.len_utf8()], format!("Invalid light '{c}'"))),
+            })
+            .collect::<Result<_, _>>()?;
         let n_lights = target.len();
 
         // following tokens until a token starting with '{' are buttons
@@ -78,52 +116,61 @@ fn parse_input(input: &str) -> Vec<Machine> {
                 joltage_tok = Some(tok);
                 break;
             }
-            let inner = &tok[1..tok.len()-1];
+            let inner = bracketed(line_no, line, tok, '(', ')')?;
             if inner.trim().is_empty() {
                 buttons.push(vec![]);
             } else {
                 let nums = inner.split(',')
-                    .map(|s| s.parse::<usize>().expect("bad index"))
-                    .collect::<Vec<_>>();
+                    .map(|s| match s.parse::<usize>() {
+                        Ok(idx) if idx < n_lights => Ok(idx),
+                        Ok(idx) => Err(Error::parse_at(line_no, line, s, format!("Light {idx} does not exist, machine has {n_lights} lights"))),
+                        Err(err) => Err(Error::parse_at(line_no, line, s, format!("bad index: {err}"))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
                 buttons.push(nums);
             }
         }
 
-        let m_buttons = buttons.len();
-
         // Parse joltage requirements {3,5,4,7}
         let joltage = if let Some(tok) = joltage_tok {
-            let inner = &tok[1..tok.len()-1];
+            let inner = bracketed(line_no, line, tok, '{', '}')?;
             if inner.trim().is_empty() {
                 vec![]
             } else {
                 inner.split(',')
-                    .map(|s| s.parse::<i64>().expect("bad joltage value"))
-                    .collect()
+                    .map(|s| s.parse::<i64>().map_err(|err| Error::parse_at(line_no, line, s, format!("bad joltage value: {err}"))))
+                    .collect::<Result<_, _>>()?
             }
         } else {
             vec![]
         };
 
-        out.push(Machine { n_lights, m_buttons, buttons, target, joltage });
+        out.push(Machine::new(buttons, target, joltage));
     }
 
-    out
+    Ok(out)
+}
+
+/// Strip the `open` and `close` delimiters around a token, e.g. `(1,3)`
+fn bracketed<'a>(line_no: usize, line: &str, tok: &'a str, open: char, close: char) -> Result<&'a str, Error> {
+    tok.strip_prefix(open)
+        .and_then(|tok| tok.strip_suffix(close))
+        .ok_or_else(|| Error::parse_at(line_no, line, tok, format!("Expected a token enclosed in {open}{close}")))
 }
 
 /// Solve one machine using Gaussian elimination in GF(2).
 /// Returns the minimum number of button presses needed.
-fn solve_machine(m: &Machine) -> Result<usize, String> {
+pub fn solve_machine(m: &Machine) -> Result<usize, Error> {
     let n_lights = m.n_lights;
     let n_buttons = m.m_buttons;
 
     if n_buttons == 0 {
         let all_off = m.target.iter().all(|&x| x == 0);
-        return if all_off { Ok(0) } else { Err("No buttons but target is not all zeros".into()) };
+        return if all_off { Ok(0) } else { Err(Error::Unsatisfiable("No buttons but target is not all zeros".into())) };
     }
 
     if n_buttons > 128 {
-        return Err("Too many buttons (>128) for u128 solver".into());
+        return Err(Error::SolverLimit("Too many buttons (>128) for u128 solver".into()));
     }
 
     // Build augmented matrix: each row represents a light (equation)
@@ -133,6 +180,10 @@ fn solve_machine(m: &Machine) -> Result<usize, String> {
     // Fill coefficient matrix: button j toggles certain lights
     for (button_idx, button) in m.buttons.iter().enumerate() {
         for &light_idx in button {
+            if light_idx >= n_lights {
+                return Err(Error::InvalidInput(format!("Button {button_idx} toggles missing light {light_idx}")));
+            }
+
             rows[light_idx].0 ^= 1u128 << button_idx;
         }
     }
@@ -146,10 +197,8 @@ fn solve_machine(m: &Machine) -> Result<usize, String> {
     let (rank, pivot_cols) = gaussian_elimination(&mut rows, n_lights, n_buttons);
 
     // Check for inconsistency (unsolvable system)
-    for r in rank..n_lights {
-        if rows[r].0 == 0 && rows[r].1 == 1 {
-            return Err("Inconsistent system: no solution".into());
-        }
+    if rows[rank..n_lights].iter().any(|&(coeffs, target)| coeffs == 0 && target == 1) {
+        return Err(Error::Unsatisfiable("Inconsistent system: no solution".into()));
     }
 
     // Find free variables (columns without pivots)
@@ -164,8 +213,9 @@ fn solve_machine(m: &Machine) -> Result<usize, String> {
 }
 
 /// Perform Gaussian elimination on the augmented matrix.
+/// Each row is (coefficients as bitmask, right-hand side bit).
 /// Returns (rank, list of pivot columns in order).
-fn gaussian_elimination(rows: &mut [(u128, u8)], n_rows: usize, n_cols: usize) -> (usize, Vec<usize>) {
+pub fn gaussian_elimination(rows: &mut [(u128, u8)], n_rows: usize, n_cols: usize) -> (usize, Vec<usize>) {
     let mut current_row = 0;
     let mut pivot_cols = Vec::new();
 
@@ -204,7 +254,7 @@ fn find_minimum_solution(
     free_vars: &[usize],
     n_buttons: usize,
     rank: usize,
-) -> Result<usize, String> {
+) -> Result<usize, Error> {
     if free_vars.is_empty() {
         // Unique solution: just read off pivot variables
         let solution = compute_solution(rows, pivot_cols, &[], 0, n_buttons);
@@ -213,7 +263,7 @@ fn find_minimum_solution(
 
     let k = free_vars.len();
     if k > 26 {
-        return Err(format!("Too many free variables ({}). Cannot enumerate 2^{} combinations", k, k));
+        return Err(Error::SolverLimit(format!("Too many free variables ({}). Cannot enumerate 2^{} combinations", k, k)));
     }
 
     // Try all 2^k combinations of free variables
@@ -256,9 +306,9 @@ fn compute_solution(
 
         // Sum contributions from all variables except the pivot
         let mut sum = 0u8;
-        for col in 0..n_buttons {
+        for (col, &value) in solution.iter().enumerate() {
             if col != pivot_col && (row_coeffs >> col) & 1 == 1 {
-                sum ^= solution[col];
+                sum ^= value;
             }
         }
 
@@ -270,8 +320,7 @@ fn compute_solution(
 
 /// Verify that a solution satisfies all equations (for debugging).
 fn verify_solution(solution: &[u8], rows: &[(u128, u8)], rank: usize) -> bool {
-    for i in 0..rank {
-        let (coeffs, target) = rows[i];
+    for &(coeffs, target) in &rows[..rank] {
         let mut sum = 0u8;
 
         for (col, &val) in solution.iter().enumerate() {
@@ -288,13 +337,13 @@ fn verify_solution(solution: &[u8], rows: &[(u128, u8)], rank: usize) -> bool {
 }
 
 /// Solve machine joltage problem (Part 2) - integer linear system
-fn solve_machine_joltage(m: &Machine) -> Result<usize, String> {
+pub fn solve_machine_joltage(m: &Machine) -> Result<usize, Error> {
     let n_counters = m.joltage.len();
     let n_buttons = m.m_buttons;
 
     if n_buttons == 0 {
         let all_zero = m.joltage.iter().all(|&x| x == 0);
-        return if all_zero { Ok(0) } else { Err("No buttons but joltage is not all zeros".into()) };
+        return if all_zero { Ok(0) } else { Err(Error::Unsatisfiable("No buttons but joltage is not all zeros".into())) };
     }
 
     // Build coefficient matrix A where A[i][j] = 1 if button j affects counter i
@@ -314,7 +363,7 @@ fn solve_machine_joltage(m: &Machine) -> Result<usize, String> {
 }
 
 /// Solve integer linear system A*x = b with x_i >= 0 and minimize sum(x)
-fn solve_integer_linear_system(matrix: &[Vec<i64>], target: &[i64], n_vars: usize) -> Result<usize, String> {
+fn solve_integer_linear_system(matrix: &[Vec<i64>], target: &[i64], n_vars: usize) -> Result<usize, Error> {
     let n_eqs = matrix.len();
 
     // Build augmented matrix for Gaussian elimination
@@ -344,11 +393,12 @@ fn solve_integer_linear_system(matrix: &[Vec<i64>], target: &[i64], n_vars: usiz
 
             // Eliminate column in other rows using integer operations
             let pivot_val = aug[current_row][col];
-            for r in 0..n_eqs {
-                if r != current_row && aug[r][col] != 0 {
-                    let factor = aug[r][col];
-                    for j in 0..=n_vars {
-                        aug[r][j] = aug[r][j] * pivot_val - aug[current_row][j] * factor;
+            let pivot_row = aug[current_row].clone();
+            for (r, row) in aug.iter_mut().enumerate() {
+                if r != current_row && row[col] != 0 {
+                    let factor = row[col];
+                    for (value, &pivot) in row.iter_mut().zip(&pivot_row) {
+                        *value = *value * pivot_val - pivot * factor;
                     }
                 }
             }
@@ -361,10 +411,8 @@ fn solve_integer_linear_system(matrix: &[Vec<i64>], target: &[i64], n_vars: usiz
     }
 
     // Check for inconsistency
-    for r in current_row..n_eqs {
-        if aug[r][n_vars] != 0 {
-            return Err("Inconsistent system".into());
-        }
+    if aug[current_row..n_eqs].iter().any(|row| row[n_vars] != 0) {
+        return Err(Error::Unsatisfiable("Inconsistent system".into()));
     }
 
     // Find free variables
@@ -374,7 +422,7 @@ fn solve_integer_linear_system(matrix: &[Vec<i64>], target: &[i64], n_vars: usiz
 
     // If system has many free variables, this is complex - for now handle small cases
     if free_vars.len() > 20 {
-        return Err(format!("Too many free variables: {}", free_vars.len()));
+        return Err(Error::SolverLimit(format!("Too many free variables: {}", free_vars.len())));
     }
 
     // Try combinations of free variables
@@ -395,7 +443,7 @@ fn solve_integer_linear_system(matrix: &[Vec<i64>], target: &[i64], n_vars: usiz
     try_free_variable_combinations(&aug, &pivot_cols, &free_vars, n_vars, max_free_val, &mut min_cost)?;
 
     if min_cost == i64::MAX {
-        return Err("No valid non-negative solution found".into());
+        return Err(Error::Unsatisfiable("No valid non-negative solution found".into()));
     }
 
     Ok(min_cost as usize)
@@ -408,7 +456,7 @@ fn try_free_variable_combinations(
     n_vars: usize,
     max_val: i64,
     min_cost: &mut i64,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     // Use iterative approach with stack to try all combinations
     if free_vars.len() == 1 {
         // Single free variable - just try values
@@ -431,6 +479,7 @@ fn try_free_variable_combinations(
     enumerate_free_vars(aug, pivot_cols, free_vars, n_vars, &mut vec![], 0, max_val, min_cost)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn enumerate_free_vars(
     aug: &[Vec<i64>],
     pivot_cols: &[(usize, usize)],
@@ -440,15 +489,12 @@ fn enumerate_free_vars(
     depth: usize,
     max_val: i64,
     min_cost: &mut i64,
-) -> Result<(), String> {
+) -> Result<(), Error> {
     if depth == free_vars.len() {
         // Try this combination
-        match compute_int_solution_with_free(aug, pivot_cols, free_vars, current_vals, n_vars) {
-            Ok(solution) => {
-                let cost: i64 = solution.iter().sum();
-                *min_cost = (*min_cost).min(cost);
-            }
-            Err(_) => {}
+        if let Ok(solution) = compute_int_solution_with_free(aug, pivot_cols, free_vars, current_vals, n_vars) {
+            let cost: i64 = solution.iter().sum();
+            *min_cost = (*min_cost).min(cost);
         }
         return Ok(());
     }
@@ -472,7 +518,7 @@ fn compute_int_solution(
     _free_vars: &[usize],
     _mask: i64,
     n_vars: usize,
-) -> Result<Vec<i64>, String> {
+) -> Result<Vec<i64>, Error> {
     let mut solution = vec![0i64; n_vars];
 
     // Compute pivot variables from equations
@@ -482,12 +528,12 @@ fn compute_int_solution(
 
         // Check if divisible
         if rhs % pivot_coeff != 0 {
-            return Err("Non-integer solution".into());
+            return Err(Error::Unsatisfiable("Non-integer solution".into()));
         }
 
         let val = rhs / pivot_coeff;
         if val < 0 {
-            return Err("Negative solution".into());
+            return Err(Error::Unsatisfiable("Negative solution".into()));
         }
 
         solution[col] = val;
@@ -502,7 +548,7 @@ fn compute_int_solution_with_free(
     free_vars: &[usize],
     free_vals: &[i64],
     n_vars: usize,
-) -> Result<Vec<i64>, String> {
+) -> Result<Vec<i64>, Error> {
     let mut solution = vec![0i64; n_vars];
 
     // Set free variables
@@ -524,16 +570,56 @@ fn compute_int_solution_with_free(
 
         // Check if divisible
         if rhs % pivot_coeff != 0 {
-            return Err("Non-integer solution".into());
+            return Err(Error::Unsatisfiable("Non-integer solution".into()));
         }
 
         let val = rhs / pivot_coeff;
         if val < 0 {
-            return Err("Negative solution".into());
+            return Err(Error::Unsatisfiable("Negative solution".into()));
         }
 
         solution[col] = val;
     }
 
     Ok(solution)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
+[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}
+";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 7);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 33);
+    }
+
+    #[test]
+    fn parses_machine() {
+        let machines = parse_input("[.#] (0) (0,1) {2,3}").unwrap();
+        assert_eq!(machines, vec![Machine::new(vec![vec![0], vec![0, 1]], vec![0, 1], vec![2, 3])]);
+    }
+
+    #[test]
+    fn malformed_machines_do_not_panic() {
+        for input in ["[", "[.#", "[.#] (0", "[.#] (2)", "[.#] (x)", "[.x] (0)", "[.#] (0) {1,y}"] {
+            assert!(matches!(parse_input(input), Err(Error::Parse(_))), "{input}");
+        }
+    }
+
+    #[test]
+    fn inconsistent_lights_are_unsatisfiable() {
+        // Both buttons toggle both lights, so they can never differ
+        let machine = Machine::new(vec![vec![0, 1], vec![0, 1]], vec![1, 0], vec![]);
+        assert!(matches!(solve_machine(&machine), Err(Error::Unsatisfiable(_))));
+    }
+}
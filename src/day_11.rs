@@ -1,20 +1,45 @@
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 use std::collections::HashMap;
 
-const TASK_VERSION: u8 = 2;
+pub struct Puzzle;
 
-pub fn rack(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => rack_01(path),
-        2 => rack_02(path),
-        _ => Err("Invalid task version!".into()),
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        11
     }
+
+    fn title(&self) -> &'static str {
+        "Reactor"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        parse_graph(input).map(drop)
+    }
+}
+
+/// Solve part one for the puzzle input file at `path`
+pub fn rack_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
 }
 
-pub fn rack_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+/// Solve part two for the puzzle input file at `path`
+pub fn rack_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-    let graph = parse_graph(&input);
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    let graph = parse_graph(input)?;
 
     let mut memo: HashMap<String, u64> = HashMap::new();
     let total = count_paths("you", &graph, &mut memo);
@@ -23,9 +48,9 @@ pub fn rack_01(path: &str) -> Result<u64, String> {
 }
 
 
-pub fn rack_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-    let graph = parse_graph(&input);
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    let graph = parse_graph(input)?;
 
     let mut memo = HashMap::new();
     let total = count_paths_with_constraints(
@@ -39,19 +64,19 @@ pub fn rack_02(path: &str) -> Result<u64, String> {
     Ok(total)
 }
 
-fn parse_graph(input: &str) -> HashMap<String, Vec<String>> {
+fn parse_graph(input: &str) -> Result<HashMap<String, Vec<String>>, Error> {
     let mut graph: HashMap<String, Vec<String>> = HashMap::new();
 
-    for line in input.lines() {
+    for (line_idx, line) in input.lines().enumerate() {
         let (from, to) = line
             .split_once(": ")
-            .expect("Invalid input format");
+            .ok_or_else(|| Error::parse_at(line_idx + 1, line, line, "Invalid input format, expected `device: outputs`"))?;
 
         let outputs = to.split_whitespace().map(String::from).collect();
         graph.insert(from.to_string(), outputs);
     }
 
-    graph
+    Ok(graph)
 }
 
 fn count_paths(
@@ -116,3 +141,52 @@ fn count_paths_with_constraints(
     memo.insert(key, sum);
     sum
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_1: &str = "\
+aaa: you hhh
+you: bbb ccc
+bbb: ddd eee
+ccc: ddd eee fff
+ddd: ggg
+eee: out
+fff: out
+ggg: out
+hhh: ccc fff iii
+iii: out
+";
+
+    const EXAMPLE_2: &str = "\
+svr: aaa bbb
+aaa: fft
+fft: ccc
+bbb: tty
+tty: ccc
+ccc: ddd eee
+ddd: hub
+hub: fff
+eee: dac
+dac: fff
+fff: ggg hhh
+ggg: out
+hhh: out
+";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE_1).unwrap(), 5);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE_2).unwrap(), 2);
+    }
+
+    #[test]
+    fn missing_separator() {
+        assert!(matches!(solve_01("you out"), Err(Error::Parse(_))));
+    }
+}
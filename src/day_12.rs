@@ -1,29 +1,61 @@
-use crate::utils::read_file;
+use std::str::FromStr;
 
-const TASK_VERSION: u8 = 1;
+use crate::error::Error;
+use crate::solution::Solution;
+use crate::utils::{ self, read_file };
 
-pub fn present(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => present_01(path),
-        2 => present_02(path),
-        _ => Err("Invalid task version!".into()),
+/// Widest region the bitset grid can hold
+pub const MAX_WIDTH: usize = u64::BITS as usize;
+
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        12
     }
+
+    fn title(&self) -> &'static str {
+        "Christmas Tree Farm"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        parse_input(input).map(drop)
+    }
+}
+
+/// Solve part one for the puzzle input file at `path`
+pub fn present_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part two for the puzzle input file at `path`
+pub fn present_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
 }
 
-pub fn present_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-    let (shapes, regions) = parse_input(&input)?;
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    let (shapes, regions) = parse_input(input)?;
     let solver = Solver::new(&shapes, &regions);
     Ok(solver.solve())
 }
 
-pub fn present_02(path: &str) -> Result<u64, String> {
-    let _input = read_file(path);
+/// Solve part two for the puzzle input text
+pub fn solve_02(_input: &str) -> Result<u64, Error> {
     // Part 2 has no defined task. Let's celebrate our success!
     Ok(0)
 }
 
-fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), String> {
+/// Parse the six present shapes followed by the region definitions
+pub fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), Error> {
     let lines: Vec<&str> = input.lines().collect();
     let mut shapes: Vec<Shape> = Vec::new();
     let mut regions: Vec<Region> = Vec::new();
@@ -37,6 +69,7 @@ fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), String> {
         i += 1;
 
         // Read the 3 lines of the shape, parse it and store it
+        let shape_start = i;
         let mut shape_lines = String::new();
         for _ in 0..3 {
             if i < lines.len() {
@@ -45,7 +78,7 @@ fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), String> {
                 i += 1;
             }
         }
-        shapes.push(Shape::from_str(&shape_lines));
+        shapes.push(shape_lines.parse::<Shape>().map_err(|err| err.offset_lines(shape_start))?);
 
         // Skip the empty line between shapes
         i += 1;
@@ -54,6 +87,7 @@ fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), String> {
     // Next up we read the region definitions
     while i < lines.len() {
         let line = lines[i];
+        let line_no = i + 1;
         if line.trim().is_empty() {
             i += 1;
             continue;
@@ -61,24 +95,25 @@ fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), String> {
 
         let parts: Vec<&str> = line.split(':').collect();
         if parts.len() != 2 {
-            return Err(format!("Invalid region definition: {}", line));
+            return Err(Error::parse_at(line_no, line, line, "Invalid region definition, expected WxH: counts"));
         }
         let dimensions: Vec<&str> = parts[0].trim().split('x').collect();
         if dimensions.len() != 2 {
-            return Err(format!("Invalid region dimensions: {}", parts[0]));
+            return Err(Error::parse_at(line_no, line, parts[0], format!("Invalid region dimensions: {}", parts[0])));
         }
         let width: usize = dimensions[0].trim().parse()
-            .map_err(|e| format!("Failed to parse width: {}", e))?;
+            .map_err(|e| Error::parse_at(line_no, line, dimensions[0], format!("Failed to parse width: {}", e)))?;
         let height: usize = dimensions[1].trim().parse()
-            .map_err(|e| format!("Failed to parse height: {}", e))?;
+            .map_err(|e| Error::parse_at(line_no, line, dimensions[1], format!("Failed to parse height: {}", e)))?;
+        if width > MAX_WIDTH {
+            return Err(Error::SolverLimit(format!("Region on line {line_no} is {width} cells wide, at most {MAX_WIDTH} are supported")));
+        }
         let shape_counts: Vec<usize> = parts[1]
-            .trim()
             .split_whitespace()
-            .map(|s| s.parse())
-            .collect::<Result<Vec<usize>, _>>()
-            .map_err(|e| format!("Failed to parse shape counts: {}", e))?;
+            .map(|s| s.parse().map_err(|e| Error::parse_at(line_no, line, s, format!("Failed to parse shape counts: {}", e))))
+            .collect::<Result<Vec<usize>, _>>()?;
         if shape_counts.len() != 6 {
-            return Err(format!("Invalid shape counts: {}", parts[1]));
+            return Err(Error::parse_at(line_no, line, parts[1], format!("Invalid shape counts: {}", parts[1])));
         }
         let region = Region {
             width,
@@ -99,75 +134,96 @@ fn parse_input(input: &str) -> Result<(Vec<Shape>, Vec<Region>), String> {
     Ok((shapes, regions))
 }
 
-struct Region {
-    width: usize,
-    height: usize,
-    shape_quantity: [usize; 6],
+/// A region under a tree and how many presents of each shape must fit into it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub width: usize,
+    pub height: usize,
+    pub shape_quantity: [usize; 6],
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Shape {
+pub struct Shape {
     rows: [u64; 3], // bit pattern for each row of 3x3 box
                     // (we use u64 to be able to shift bits into the u64 width grid...)
     size: usize,    // number of # in the box
 }
 
+// parse a shape from a 3-line string
+impl FromStr for Shape {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = utils::Grid::parse(s)?;
+
+        if cells.width() != 3 || cells.height() != 3 {
+            let line = s.lines().next().unwrap_or("");
+            return Err(Error::parse_at(1, line, line, format!("Expected a 3x3 shape, found {}x{}", cells.width(), cells.height())));
+        }
+
+        let mut filled = utils::Grid::filled(3, 3, false);
+        for (row, col) in cells.positions() {
+            filled[(row, col)] = match cells[(row, col)] {
+                b'#' => true,
+                b'.' => false,
+                other => {
+                    let line = s.lines().nth(row).unwrap_or("");
+                    return Err(Error::parse(row + 1, line, col + 1, format!("Invalid shape cell '{}'", other as char)));
+                },
+            };
+        }
+
+        Ok(Shape::from_grid(&filled))
+    }
+}
+
 impl Shape {
-    // parse a shape from a 3-line string
-    fn from_str(s: &str) -> Self {
+    /// Shape made of the filled cells in the top left 3x3 corner of `grid`
+    pub fn from_grid(grid: &utils::Grid<bool>) -> Shape {
         let mut rows = [0u64; 3];
         let mut size = 0;
 
-        for (y, line) in s.lines().enumerate().take(3) {
-            for (x, char) in line.chars().enumerate().take(3) {
-                if char == '#' {
-                    rows[y] |= 1 << x;  // set the bit for the #
-                    size += 1;          // increment size
-                }
+        for (row, col) in grid.positions().filter(|&(row, col)| row < 3 && col < 3) {
+            if grid[(row, col)] {
+                rows[row] |= 1 << col;  // set the bit for the #
+                size += 1;              // increment size
             }
         }
 
         Shape { rows, size }
     }
 
-    // wee need to rotate the box 90 degrees clockwise
-    fn rotate_box(&self) -> Shape {
-        let mut new_rows = [0u64; 3];
-
-        for y in 0..3 {
-            for x in 0..3 {
-                if (self.rows[y] & (1 << x)) != 0 {
-                    new_rows[x] |= 1 << (2 - y);
-                }
-            }
+    /// The 3x3 box of the shape, `true` for filled cells
+    pub fn to_grid(&self) -> utils::Grid<bool> {
+        let mut grid = utils::Grid::filled(3, 3, false);
+        for (row, col) in grid.positions() {
+            grid[(row, col)] = self.rows[row] & (1 << col) != 0;
         }
+        grid
+    }
 
-        Shape {
-            rows: new_rows,
-            size: self.size,
-        }
+    /// Bit pattern of each row, bit `x` set means the cell at column `x` is filled
+    pub fn rows(&self) -> [u64; 3] {
+        self.rows
     }
 
-    // we also need to be able to flip the box horizontally
-    fn flip_box(&self) -> Shape {
-        let mut new_rows = [0u64; 3];
+    /// Number of filled cells
+    pub fn size(&self) -> usize {
+        self.size
+    }
 
-        for y in 0..3 {
-            for x in 0..3 {
-                if (self.rows[y] & (1 << x)) != 0 {
-                    new_rows[y] |= 1 << (2 - x);
-                }
-            }
-        }
+    // wee need to rotate the box 90 degrees clockwise
+    pub fn rotate_box(&self) -> Shape {
+        Shape::from_grid(&self.to_grid().rotate_cw())
+    }
 
-        Shape {
-            rows: new_rows,
-            size: self.size,
-        }
+    // we also need to be able to flip the box horizontally
+    pub fn flip_box(&self) -> Shape {
+        Shape::from_grid(&self.to_grid().flip_horizontal())
     }
 
     // let's generate all variants of this shape (4 rotations, each flipped and unflipped))
-    fn generate_variants(&self) -> Vec<Shape> {
+    pub fn generate_variants(&self) -> Vec<Shape> {
         let mut variants = Vec::new();
         let mut current = *self;
 
@@ -185,14 +241,15 @@ impl Shape {
     }
 }
 
-struct Grid {
+/// Occupancy bitset of a region, at most `MAX_WIDTH` cells wide
+pub struct Grid {
     rows: Vec<u64>, // u64 is wide enough for our purposes here, each bit represents a cell in the grid
     width: usize,   // actual width of the grid
     height: usize,  // actual height of the grid
 }
 
 impl Grid {
-    fn new(width: usize, height: usize) -> Self {
+    pub fn new(width: usize, height: usize) -> Self {
         Grid {
             rows: vec![0u64; height],
             width,
@@ -200,8 +257,16 @@ impl Grid {
         }
     }
 
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
     // can the shape fit at this position?
-    fn can_fit(&self, shape: &Shape, x: usize, y: usize) -> bool {
+    pub fn can_fit(&self, shape: &Shape, x: usize, y: usize) -> bool {
         // is there room left at all?
         if x + 3 > self.width || y + 3 > self.height {
             return false;
@@ -218,7 +283,7 @@ impl Grid {
     }
 
     // let's put the shape here
-    fn place_shape(&mut self, shape: &Shape, x: usize, y: usize) {
+    pub fn place_shape(&mut self, shape: &Shape, x: usize, y: usize) {
         for row in 0..3 {
             let shape_row_bits = shape.rows[row] << x;
             self.rows[y + row] |= shape_row_bits;
@@ -226,7 +291,7 @@ impl Grid {
     }
 
     // remove the shape from here
-    fn remove_shape(&mut self, shape: &Shape, x: usize, y: usize) {
+    pub fn remove_shape(&mut self, shape: &Shape, x: usize, y: usize) {
         for row in 0..3 {
             let shape_row_bits = shape.rows[row] << x;
             self.rows[y + row] &= !shape_row_bits;
@@ -235,14 +300,15 @@ impl Grid {
 
 }
 
-struct Solver<'a> {
+/// Backtracking packer deciding which regions can fit all of their presents
+pub struct Solver<'a> {
     _shapes: &'a [Shape],
     regions: &'a [Region],
     shape_variants: Vec<Vec<Shape>>,
 }
 
 impl<'a> Solver<'a> {
-    fn new(shapes: &'a [Shape], regions: &'a [Region]) -> Self {
+    pub fn new(shapes: &'a [Shape], regions: &'a [Region]) -> Self {
         let mut shape_variants = Vec::new();
         for shape in shapes {
             shape_variants.push(shape.generate_variants());
@@ -282,34 +348,122 @@ impl<'a> Solver<'a> {
         false // no valid placement found
     }
 
-    fn solve(&self) -> u64 {
-        let mut count: u64 = 0;
+    /// Can all presents of the region be packed into it?
+    pub fn fits(&self, region: &Region) -> bool {
+        let mut total_present_size = 0;
+        let mut present_list = Vec::new();
+        for (i, &count) in region.shape_quantity.iter().enumerate() {
+            for _ in 0..count {
+                present_list.push(&self.shape_variants[i]);
+                total_present_size += self.shape_variants[i][0].size;
+            }
+        }
+
+        // Leave early if there is not enough space at all
+        if total_present_size > region.width * region.height {
+            return false;
+        }
+
+        // Sort the shape list by area descending for hopefully a bit more luck with the placement
+        present_list.sort_by_key(|s| usize::MAX - s[0].size);
 
+        let mut grid = Grid::new(region.width, region.height);
+        self.fit_the_presents(&mut grid, &present_list, 0)
+    }
+
+    /// Count the regions that can fit all of their presents
+    pub fn solve(&self) -> u64 {
         // Check each region one by one and count how many can fit the presents
-        for region in self.regions {
-            let mut total_present_size = 0;
-            let mut present_list = Vec::new();
-            for (i, &count) in region.shape_quantity.iter().enumerate() {
-                for _ in 0..count {
-                    present_list.push(&self.shape_variants[i]);
-                    total_present_size += self.shape_variants[i][0].size;
-                }
-            }
+        self.regions.iter().filter(|region| self.fits(region)).count() as u64
+    }
+}
 
-            // Leave early if there is not enough space at all
-            if total_present_size > region.width * region.height {
-                continue;
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+0:
+###
+##.
+##.
+
+1:
+###
+##.
+.##
+
+2:
+.##
+###
+##.
+
+3:
+##.
+###
+##.
+
+4:
+###
+#..
+###
+
+5:
+###
+.#.
+###
+
+4x4: 0 0 0 0 2 0
+12x5: 1 0 1 0 2 2
+12x5: 1 0 1 0 3 2
+";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 2);
+    }
 
-            // Sort the shape list by area descending for hopefully a bit more luck with the placement
-            present_list.sort_by_key(|s| usize::MAX - s[0].size);
+    /// Every 3x3 shape, built from the 9 bits of `mask`
+    fn shape_from_mask(mask: u32) -> Shape {
+        let text: String = (0..3)
+            .map(|y| (0..3).map(|x| if mask & (1 << (y * 3 + x)) != 0 { '#' } else { '.' }).collect::<String>() + "\n")
+            .collect();
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn generate_variants_is_closed_under_symmetries() {
+        for mask in 0..1 << 9 {
+            let shape = shape_from_mask(mask);
+            let variants = shape.generate_variants();
 
-            let mut grid = Grid::new(region.width, region.height);
-            if self.fit_the_presents(&mut grid, &present_list, 0) {
-                count += 1;
+            assert!(variants.contains(&shape));
+            assert!([1, 2, 4, 8].contains(&variants.len()), "{} variants", variants.len());
+
+            for (i, variant) in variants.iter().enumerate() {
+                assert_eq!(variant.size(), shape.size());
+                assert!(variants.contains(&variant.rotate_box()));
+                assert!(variants.contains(&variant.flip_box()));
+                assert!(!variants[i + 1..].contains(variant), "duplicate variant");
             }
         }
+    }
+
+    #[test]
+    fn rotating_four_times_is_identity() {
+        for mask in 0..1 << 9 {
+            let shape = shape_from_mask(mask);
+            assert_eq!(shape.rotate_box().rotate_box().rotate_box().rotate_box(), shape);
+            assert_eq!(shape.flip_box().flip_box(), shape);
+        }
+    }
+
+    #[test]
+    fn shape_round_trips_through_grid() {
+        let shape: Shape = "##.\n.#.\n###\n".parse().unwrap();
 
-        count
+        assert_eq!(shape.to_grid().to_string(), "##.\n.#.\n###");
+        assert_eq!(shape.rotate_box().to_grid().to_string(), "#.#\n###\n#..");
+        assert!(matches!("##\n.#\n".parse::<Shape>(), Err(Error::Parse(_))));
     }
 }
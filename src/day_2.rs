@@ -1,110 +1,661 @@
+use std::collections::BTreeMap;
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 
-const TASK_VERSION: u8 = 2;
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        2
+    }
+
+    fn title(&self) -> &'static str {
+        "Gift Shop"
+    }
 
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
 
-pub fn invalid_ids(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => invalid_ids_01(path),
-        2 => invalid_ids_02(path),
-        _ => Err("Invalid task version!".into()),
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        RangeReader::new(input.as_bytes()).try_for_each(|range| range.map(drop))
     }
 }
 
-pub fn invalid_ids_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-    let ranges = parse_input(input)?;
+/// Solve part one for the puzzle input file at `path`
+pub fn invalid_ids_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-    let mut result = 0;
-    for range in ranges {
-        let (start, end) = range;
+/// Solve part two for the puzzle input file at `path`
+pub fn invalid_ids_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-        let ids = start..=end;
-        for id in ids {
-            let str_id = id.to_string();
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    to_answer(sum_matching(input.as_bytes(), &Exactly(2))?)
+}
 
-            // Only evaluate even-length ids for invalidity
-            if str_id.len() % 2 != 1 {
-                let splitted_id = str_id.split_at(str_id.len() / 2);
-                if splitted_id.0 == splitted_id.1 {
-                    result += id;
-                }
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    to_answer(sum_matching(input.as_bytes(), &AtLeast(2))?)
+}
+
+/// Sum of the ids matching `pattern` over every range read from `reader`, one range at a time
+pub fn sum_matching(reader: impl BufRead, pattern: &impl IdPattern) -> Result<u128, Error> {
+    RangeReader::new(reader).try_fold(0u128, |total, range| {
+        let (start, end) = range?;
+        pattern.sum(start, end)
+            .and_then(|sum| total.checked_add(sum))
+            .ok_or_else(|| Error::SolverLimit("Sum of the invalid ids does not fit into u128".into()))
+    })
+}
+
+/// Narrow a sum down to the u64 puzzle answer
+fn to_answer(sum: u128) -> Result<u64, Error> {
+    u64::try_from(sum).map_err(|_| Error::SolverLimit(format!("Sum of the invalid ids {sum} does not fit into u64")))
+}
+
+/// Rule telling invalid ids apart, to be handed to [`sum_matching`].
+///
+/// [`Exactly`], [`AtLeast`] and [`Palindrome`] are built in. Another rule only needs [`matches`](IdPattern::matches),
+/// the defaults then scan the range id by id, and can enumerate or sum its ids faster by overriding the others.
+pub trait IdPattern {
+    fn matches(&self, id: u128) -> bool;
+
+    /// Every id in `start..=end` matching the rule, ascending
+    fn ids(&self, start: u128, end: u128) -> impl Iterator<Item = u128> {
+        (start..=end).filter(|&id| self.matches(id))
+    }
+
+    /// Sum of the ids in `start..=end` matching the rule, `None` if it does not fit into u128
+    fn sum(&self, start: u128, end: u128) -> Option<u128> {
+        self.ids(start, end).try_fold(0u128, u128::checked_add)
+    }
+}
+
+/// One digit block written exactly `k` times, 1212 for `Exactly(2)`. `Exactly(1)` matches every id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exactly(pub u32);
+
+/// One digit block written `k` or more times, 1212 or 121212 for `AtLeast(2)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtLeast(pub u32);
+
+/// Digits reading the same both ways, 12321
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palindrome;
+
+impl IdPattern for Exactly {
+    fn matches(&self, id: u128) -> bool {
+        self.ids(id, id).next().is_some()
+    }
+
+    fn ids(&self, start: u128, end: u128) -> impl Iterator<Item = u128> {
+        repeated_ids(start, end, self.0..=self.0)
+    }
+
+    fn sum(&self, start: u128, end: u128) -> Option<u128> {
+        repeated_sum(start, end, self.0..=self.0)
+    }
+}
+
+impl IdPattern for AtLeast {
+    fn matches(&self, id: u128) -> bool {
+        self.ids(id, id).next().is_some()
+    }
+
+    fn ids(&self, start: u128, end: u128) -> impl Iterator<Item = u128> {
+        repeated_ids(start, end, self.0..=u32::MAX)
+    }
+
+    fn sum(&self, start: u128, end: u128) -> Option<u128> {
+        repeated_sum(start, end, self.0..=u32::MAX)
+    }
+}
+
+impl IdPattern for Palindrome {
+    fn matches(&self, id: u128) -> bool {
+        let digits = id.to_string();
+        digits.bytes().eq(digits.bytes().rev())
+    }
+
+    fn ids(&self, start: u128, end: u128) -> impl Iterator<Item = u128> {
+        palindromes(start, end)
+    }
+}
+
+/// Every id in `start..=end` made of one digit block written `k` times for some `k` in `repeats`, ascending.
+///
+/// An id of `len` digits built from a block of `block_len` digits is `block * (10^len - 1) / (10^block_len - 1)`,
+/// e.g. `123123 = 123 * 1001`, so the matching blocks form a contiguous range and no other id is looked at.
+/// The ids are produced lazily, merging the block lengths of each id length.
+pub fn repeated_ids(start: u128, end: u128, repeats: RangeInclusive<u32>) -> RepeatedIds {
+    let len = digit_count(start);
+
+    RepeatedIds { start, end, repeats, len, blocks: Vec::new() }
+}
+
+/// Iterator over the ids of [`repeated_ids`]
+#[derive(Debug, Clone)]
+pub struct RepeatedIds {
+    start: u128,
+    end: u128,
+    repeats: RangeInclusive<u32>,
+    /// Digit count of the ids after the current ones
+    len: u32,
+    /// Blocks still to repeat for each block length of the current digit count
+    blocks: Vec<BlockRange>,
+}
+
+/// Blocks of one length that repeat into ids within the range
+#[derive(Debug, Clone, Copy)]
+struct BlockRange {
+    next: u128,
+    last: u128,
+    multiplier: u128,
+}
+
+impl Iterator for RepeatedIds {
+    type Item = u128;
+
+    fn next(&mut self) -> Option<u128> {
+        loop {
+            if let Some(id) = self.blocks.iter().map(|blocks| blocks.next * blocks.multiplier).min() {
+                // An id like 1111 is both 11 twice and 1 four times, every block length producing it moves on
+                self.blocks.retain_mut(|blocks| {
+                    if blocks.next * blocks.multiplier != id {
+                        true
+                    } else if blocks.next == blocks.last {
+                        false
+                    } else {
+                        blocks.next += 1;
+                        true
+                    }
+                });
+
+                return Some(id);
+            }
+
+            if self.start > self.end || self.len > digit_count(self.end) {
+                return None;
             }
+
+            let len = self.len;
+            self.blocks = (1..=len)
+                .filter(|&block_len| len.is_multiple_of(block_len) && self.repeats.contains(&(len / block_len)))
+                .filter_map(|block_len| {
+                    let (next, last, multiplier) = block_range(self.start, self.end, len, block_len)?;
+                    Some(BlockRange { next, last, multiplier })
+                })
+                .collect();
+            self.len += 1;
+        }
+    }
+}
+
+/// Sum of [`repeated_ids`] without producing them, `None` if it does not fit into u128.
+///
+/// The ids of `len` digits repeating a block of `d` digits sum to `multiplier * (first + last) * count / 2`
+/// over their blocks. Those include the ids whose shortest repeating block is shorter and divides `d`,
+/// e.g. 1111 for `d = 2`, so the sum of the ids whose shortest block is exactly `d` is what remains
+/// after subtracting those of every proper divisor of `d`. Adding the latter up for every block length
+/// dividing an allowed one counts every matching id exactly once.
+pub fn repeated_sum(start: u128, end: u128, repeats: RangeInclusive<u32>) -> Option<u128> {
+    if start > end {
+        return Some(0);
+    }
+
+    let mut total: u128 = 0;
+
+    for len in digit_count(start)..=digit_count(end) {
+        let allowed: Vec<u32> = (1..=len)
+            .filter(|&block_len| len.is_multiple_of(block_len) && repeats.contains(&(len / block_len)))
+            .collect();
+        let block_lens: Vec<u32> = (1..=len)
+            .filter(|&block_len| allowed.iter().any(|allowed| allowed.is_multiple_of(block_len)))
+            .collect();
+
+        // Sum of the ids with shortest block exactly `block_lens[idx]`, shortest block lengths first
+        let mut primitive: Vec<u128> = Vec::with_capacity(block_lens.len());
+        for &block_len in &block_lens {
+            let all = match block_range(start, end, len, block_len) {
+                Some((first, last, multiplier)) => multiplier.checked_mul(series_sum(first, last)?)?,
+                None => 0,
+            };
+
+            let shorter = block_lens.iter()
+                .zip(&primitive)
+                .filter(|&(&shorter, _)| block_len.is_multiple_of(shorter))
+                .map(|(_, &sum)| sum)
+                .sum::<u128>();
+            primitive.push(all - shorter);
         }
+
+        total = total.checked_add(primitive.iter().sum())?;
     }
 
-    Ok(result)
+    Some(total)
+}
+
+/// First and last block of `block_len` digits repeating into an id of `len` digits within `start..=end`,
+/// and the multiplier turning a block into its id. `None` if there is no such block.
+fn block_range(start: u128, end: u128, len: u32, block_len: u32) -> Option<(u128, u128, u128)> {
+    // 1 followed by `block_len - 1` zeros, repeated; 10^len itself may not fit for 39 digit ids
+    let multiplier = (1..len / block_len).fold(1u128, |multiplier, _| multiplier * 10u128.pow(block_len) + 1);
+    // 0 is the only id with a leading zero, a single digit written once
+    let lowest = if len == 1 { 0 } else { 10u128.pow(block_len - 1) };
+    let highest = 10u128.checked_pow(block_len).map_or(u128::MAX, |power| power - 1);
+    let first = lowest.max(start.div_ceil(multiplier));
+    let last = highest.min(end / multiplier);
+
+    (first <= last).then_some((first, last, multiplier))
 }
 
-pub fn invalid_ids_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-    let ranges = parse_input(input)?;
+/// `first + (first + 1) + ... + last`, `None` if it does not fit into u128
+fn series_sum(first: u128, last: u128) -> Option<u128> {
+    let count = last - first + 1;
+    // Of `count` and `first + last`, one is even
+    if count.is_multiple_of(2) {
+        (count / 2).checked_mul(first.checked_add(last)?)
+    } else {
+        count.checked_mul(first + (last - first) / 2)
+    }
+}
+
+/// Every palindrome in `start..=end`, ascending, built from the first half of its digits
+pub fn palindromes(start: u128, end: u128) -> impl Iterator<Item = u128> {
+    // No digit count at all for an empty range
+    let last_len = if start <= end { digit_count(end) } else { 0 };
 
-    let mut result = 0;
-    for range in ranges {
-        let (start, end) = range;
+    (digit_count(start)..=last_len).flat_map(move |len| {
+        let half_len = len.div_ceil(2);
+        // 0 is the only palindrome with a leading zero
+        let lowest = if len == 1 { 0 } else { 10u128.pow(half_len - 1) };
+        let highest = 10u128.pow(half_len) - 1;
+
+        // Mirroring is monotonic, so only the halves of the bounds themselves can fall outside
+        let first = lowest.max(start / 10u128.pow(len - half_len));
+        let last = highest.min(end / 10u128.pow(len - half_len));
+        (first..=last).map_while(move |half| mirror(half, len))
+    })
+    .filter(move |id| (start..=end).contains(id))
+}
 
-        let ids = start..=end;
-        for id in ids {
-            let str_id = id.to_string();
+/// Palindrome of `len` digits starting with the digits of `half`, `None` past u128::MAX
+fn mirror(half: u128, len: u32) -> Option<u128> {
+    let mut id = half;
+    let mut rest = if len % 2 == 1 { half / 10 } else { half };
+    while rest > 0 {
+        id = id.checked_mul(10)?.checked_add(rest % 10)?;
+        rest /= 10;
+    }
+    Some(id)
+}
 
-            // Take first char, then compare it with next sequence until reaching end or not matching 
-            // If match not found, increase the window
-            // Repeat until reaching half of the length of the id
+fn digit_count(number: u128) -> u32 {
+    number.checked_ilog10().unwrap_or(0) + 1
+}
 
-            let id_len = str_id.len();
-            let mut window_len = 1;
-            while window_len <= id_len / 2 {
-                let splitted_id = str_id.split_at(window_len);
-                let current_seq = splitted_id.0;
-                let rest_of_id = splitted_id.1;
+/// Lazily reads `start-end` ranges separated by commas or newlines from any [`BufRead`],
+/// one range at a time, so huge range lists are never held in memory as a whole.
+/// Ids are read as u128, the type every [`IdPattern`] works with, so they may go past `u64::MAX`.
+///
+/// Overlap reporting is opt-in through [`track_overlaps`](RangeReader::track_overlaps), as it has to remember
+/// the ranges read so far, which takes memory in proportion to the number of disjoint ranges.
+///
+/// Parse errors point into the offending range rather than the whole line, which may be enormous.
+/// The iterator ends after the first error.
+pub struct RangeReader<R> {
+    reader: R,
+    line_no: usize,
+    done: bool,
+    /// Union of the ranges read so far as disjoint `start -> end` spans, if overlaps are tracked
+    seen: Option<BTreeMap<u128, u128>>,
+    overlaps: Vec<Overlap>,
+}
 
-                if id_len % window_len != 0 {
-                    window_len += 1;
-                    continue; 
+/// A range that shares ids with a range read before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlap {
+    pub line: usize,
+    pub range: (u128, u128),
+}
+
+impl<R: BufRead> RangeReader<R> {
+    pub fn new(reader: R) -> Self {
+        RangeReader { reader, line_no: 1, done: false, seen: None, overlaps: Vec::new() }
+    }
+
+    /// Also note every range that overlaps an earlier one, at the cost of remembering the ranges read
+    pub fn track_overlaps(mut self) -> Self {
+        self.seen = Some(BTreeMap::new());
+        self
+    }
+
+    /// Ranges read so far that overlap an earlier range, in input order.
+    /// Always empty unless overlaps are tracked.
+    pub fn overlaps(&self) -> &[Overlap] {
+        &self.overlaps
+    }
+
+    /// Next non-empty item with the line it is on
+    fn read_item(&mut self) -> Result<Option<(usize, String)>, Error> {
+        let io_err = |source| Error::Io { path: "<input>".into(), source };
+
+        loop {
+            let line_no = self.line_no;
+            let mut item = Vec::new();
+
+            let separator = loop {
+                let buf = self.reader.fill_buf().map_err(io_err)?;
+                if buf.is_empty() {
+                    break None;
                 }
-                
-                let windows: Vec<&str> = rest_of_id.as_bytes()
-                    .chunks_exact(window_len)  // only full chunks
-                    .map(|buf| unsafe { str::from_utf8_unchecked(buf) })
-                    .collect();
-
-                let all_match = windows.iter().all(|window| *window == current_seq);
-                if all_match {
-                    result += id;
-                    break;
+
+                match buf.iter().position(|byte| matches!(byte, b',' | b'\n')) {
+                    Some(idx) => {
+                        let separator = buf[idx];
+                        item.extend_from_slice(&buf[..idx]);
+                        self.reader.consume(idx + 1);
+                        break Some(separator);
+                    },
+                    None => {
+                        let len = buf.len();
+                        item.extend_from_slice(buf);
+                        self.reader.consume(len);
+                    },
                 }
+            };
+
+            if separator == Some(b'\n') {
+                self.line_no += 1;
+            }
+
+            let item = String::from_utf8(item).map_err(|err| {
+                let item = String::from_utf8_lossy(err.as_bytes()).to_string();
+                Error::parse_at(line_no, &item, &item, "Range is not valid UTF-8")
+            })?;
+            let item = item.trim();
+            if !item.is_empty() {
+                return Ok(Some((line_no, item.to_string())));
+            }
+            if separator.is_none() {
+                return Ok(None);
+            }
+        }
+    }
+
+    fn parse_range(line_no: usize, item: &str) -> Result<(u128, u128), Error> {
+        let (str_start, str_end) = item
+            .split_once('-')
+            .ok_or_else(|| Error::parse_at(line_no, item, item, format!("No end for range {item} found!")))?;
+
+        let start = str_start
+            .parse::<u128>()
+            .map_err(|err| Error::parse_at(line_no, item, str_start, format!("Cannot parse start of range: {err}")))?;
+        let end = str_end
+            .parse::<u128>()
+            .map_err(|err| Error::parse_at(line_no, item, str_end, format!("Cannot parse end of range: {err}")))?;
+
+        if start > end {
+            return Err(Error::parse_at(line_no, item, item, format!("Range {item} ends before it starts")));
+        }
+
+        Ok((start, end))
+    }
 
-                window_len += 1;
+    /// Add `range` to the ranges seen so far, true if it shares ids with any of them
+    fn record(seen: &mut BTreeMap<u128, u128>, (start, end): (u128, u128)) -> bool {
+        let mut merged = (start, end);
+        let mut overlapping = false;
+
+        // The spans are disjoint, so only the last one starting within the range can reach into it
+        while let Some((&span_start, &span_end)) = seen.range(..=merged.1).next_back() {
+            if span_end < merged.0 {
+                break;
             }
-            
+
+            overlapping = true;
+            seen.remove(&span_start);
+            merged = (span_start.min(merged.0), span_end.max(merged.1));
         }
+
+        seen.insert(merged.0, merged.1);
+        overlapping
     }
+}
+
+impl<R: BufRead> Iterator for RangeReader<R> {
+    type Item = Result<(u128, u128), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
 
-    Ok(result)
+        let parsed = match self.read_item() {
+            Ok(Some((line_no, item))) => Self::parse_range(line_no, &item).map(|range| (line_no, range)),
+            Ok(None) => {
+                self.done = true;
+                return None;
+            },
+            Err(err) => Err(err),
+        };
+
+        match parsed {
+            Ok((line_no, range)) => {
+                if self.seen.as_mut().is_some_and(|seen| Self::record(seen, range)) {
+                    self.overlaps.push(Overlap { line: line_no, range });
+                }
+                Some(Ok(range))
+            },
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            },
+        }
+    }
 }
 
-fn parse_input(input: String) -> Result<Vec<(u64, u64)>, String> {
-    let str_ranges = input.split(',');
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Rng;
+    use std::io::BufReader;
+
+    const EXAMPLE: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,\
+1698522-1698528,446443-446449,38593856-38593862,565653-565659,\
+824824821-824824827,2121212118-2121212124";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 1227775554);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 4174379265);
+    }
+
+    /// Straightforward check on the decimal digits
+    fn is_repeated(id: u128, repeats: &RangeInclusive<u32>) -> bool {
+        let digits = id.to_string();
+        let len = digits.len();
 
-    let mut ranges: Vec<(u64, u64)> = Vec::new();
-    for range in str_ranges {
-        let range = range.trim();
-        let mut start_end_split = range.split('-');
+        (1..=len).filter(|block_len| len.is_multiple_of(*block_len)).any(|block_len| {
+            repeats.contains(&((len / block_len) as u32))
+                && digits.as_bytes().chunks(block_len).all(|block| block == &digits.as_bytes()[..block_len])
+        })
+    }
+
+    #[test]
+    fn generates_the_same_ids_as_scanning() {
+        let mut rng = Rng::new(2);
+
+        for _ in 0..200 {
+            let start = if rng.range(0..=3) == 0 { 0 } else { u128::from(rng.range(0..=1_000_000)) };
+            let end = start + u128::from(rng.range(0..=20_000));
+            let repeats = match rng.range(0..=4) {
+                0 => 2..=2,
+                1 => 2..=u32::MAX,
+                2 => 1..=1,
+                3 => 1..=u32::MAX,
+                _ => 3..=4,
+            };
+
+            let scanned: Vec<u128> = (start..=end).filter(|&id| is_repeated(id, &repeats)).collect();
+            assert_eq!(repeated_ids(start, end, repeats.clone()).collect::<Vec<_>>(), scanned, "{start}-{end} {repeats:?}");
+            assert_eq!(repeated_sum(start, end, repeats.clone()), Some(scanned.iter().sum()));
+        }
+    }
+
+    #[test]
+    fn handles_wide_ranges() {
+        // Two equal halves of 2 to 12 digits: 9 + 90 + ... + 900_000
+        assert_eq!(repeated_ids(1, 10u128.pow(12), 2..=2).count(), 999_999);
+        assert_eq!(repeated_sum(1, 10u128.pow(12), 2..=2), Some(495_495_540_949_540_950));
+        // 6 digit ids repeat blocks of 3 (900) or 2 (90) digits, the 9 repdigits being both
+        assert_eq!(repeated_ids(1, 999_999, 2..=u32::MAX).count(), 9 + 9 + 90 + 9 + 981);
+        assert!(repeated_ids(18_446_744_070_000_000_000, u64::MAX.into(), 2..=2).eq([18_446_744_071_844_674_407]));
+        assert_eq!(repeated_ids(u128::MAX - 10, u128::MAX, 1..=1).count(), 11);
+        assert!(repeated_ids(u128::MAX - 10u128.pow(26), u128::MAX, 3..=u32::MAX).eq([340_282_366_920_934_028_236_692_093_402_823_669_209]));
+        assert_eq!(repeated_ids(5, 4, 2..=2).next(), None);
+        assert_eq!(repeated_sum(5, 4, 2..=2), Some(0));
+    }
+
+    #[test]
+    fn reports_sums_beyond_u64() {
+        // Ten million ids, summed without being produced
+        assert_eq!(repeated_sum(1, 99_999_999_999_999, 2..=u32::MAX), Some(495_504_906_526_544_176_800));
+        assert!(matches!(solve_02("1-99999999999999"), Err(Error::SolverLimit(_))));
+        assert!(matches!(solve_02("1-999999999999999999"), Err(Error::SolverLimit(_))));
+        assert!(repeated_sum(0, u64::MAX.into(), 1..=u32::MAX).is_some());
+        assert_eq!(solve_01("1-1000000000000").unwrap(), 495_495_540_949_540_950);
+    }
+
+    /// Custom rule relying on the default enumeration
+    struct DivisibleBy(u128);
+
+    impl IdPattern for DivisibleBy {
+        fn matches(&self, id: u128) -> bool {
+            id.is_multiple_of(self.0)
+        }
+    }
+
+    fn assert_enumerates_matches(pattern: &impl IdPattern, start: u128, end: u128) {
+        let scanned: Vec<u128> = (start..=end).filter(|&id| pattern.matches(id)).collect();
+        assert_eq!(pattern.ids(start, end).collect::<Vec<_>>(), scanned, "{start}-{end}");
+        assert_eq!(pattern.sum(start, end), Some(scanned.iter().sum()));
+    }
+
+    #[test]
+    fn patterns_enumerate_what_they_match() {
+        let mut rng = Rng::new(19);
+
+        for _ in 0..100 {
+            let start = u128::from(rng.range(0..=10_000_000));
+            let end = start + u128::from(rng.range(0..=5_000));
+
+            assert_enumerates_matches(&Exactly(3), start, end);
+            assert_enumerates_matches(&AtLeast(2), start, end);
+            assert_enumerates_matches(&Palindrome, start, end);
+        }
+    }
+
+    #[test]
+    fn single_repetition_includes_zero() {
+        assert!(Exactly(1).matches(0));
+        assert!(AtLeast(1).matches(0));
+        assert!(Exactly(1).ids(0, 3).eq([0, 1, 2, 3]));
+        assert_eq!(AtLeast(1).sum(0, 3), Some(6));
+        assert!(!Exactly(2).matches(0));
+    }
+
+    #[test]
+    fn matches_palindromes() {
+        assert!(Palindrome.matches(0));
+        assert!(Palindrome.matches(12321));
+        assert!(Palindrome.matches(1221));
+        assert!(!Palindrome.matches(1231));
+        assert!(palindromes(0, 30).eq([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 11, 22]));
+        assert!(palindromes(u128::from(u64::MAX) - 10_000_000_000, u64::MAX.into()).eq([18_446_744_066_044_764_481]));
+        assert_eq!(palindromes(u128::MAX - 10u128.pow(20), u128::MAX).count(), 9);
+        assert_eq!(palindromes(5, 4).next(), None);
+        // 11-22 has 11 and 22, 95-115 has 99, 101 and 111
+        assert_eq!(sum_matching("11-22,95-115".as_bytes(), &Palindrome).unwrap(), 11 + 22 + 99 + 101 + 111);
+        assert_eq!(sum_matching(EXAMPLE.as_bytes(), &Exactly(3)).unwrap(), 111 + 999 + 222_222 + 565_656 + 824_824_824);
+    }
+
+    #[test]
+    fn solves_ranges_beyond_u64() {
+        assert!(Exactly(1).ids(u128::MAX - 3, u128::MAX).eq([u128::MAX - 3, u128::MAX - 2, u128::MAX - 1, u128::MAX]));
+        assert_eq!(Exactly(1).sum(u128::MAX - 3, u128::MAX), None);
+
+        // 20 digit ids made of two equal halves, past u64::MAX
+        let input = "99999999989999999998-100000000000000000000";
+        let sum = 99_999_999_989_999_999_998 + 99_999_999_999_999_999_999;
+        assert_eq!(sum_matching(input.as_bytes(), &Exactly(2)).unwrap(), sum);
+        assert!(matches!(solve_01(input), Err(Error::SolverLimit(_))));
+    }
+
+    #[test]
+    fn takes_custom_patterns() {
+        assert_enumerates_matches(&DivisibleBy(7), 0, 100);
+        assert_eq!(sum_matching("1-20,50-50".as_bytes(), &DivisibleBy(10)).unwrap(), 10 + 20 + 50);
+    }
 
-        let start = start_end_split
-            .next()
-            .ok_or(format!("No start for range {range} found!"))?
-            .parse::<u64>()
-            .map_err(|err| format!("Cannot parse start of range: {err}"))?;
-        let end = start_end_split
-            .next()
-            .ok_or(format!("No end for range {range} found!"))?
-            .parse::<u64>()
-            .map_err(|err| format!("Cannot parse end of range: {err}"))?;
+    #[test]
+    fn parses_ranges_over_multiple_lines() {
+        let ranges = RangeReader::new("1-2,\n3-4\n".as_bytes()).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(ranges, [(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn streams_ranges_in_small_reads() {
+        let input = "1-2, 3-4,\n\n5-6\n340282366920938463463374607431768211454-340282366920938463463374607431768211455";
+        // Tiny buffer so items straddle reads
+        let reader = BufReader::with_capacity(3, input.as_bytes());
+        let ranges: Vec<(u128, u128)> = RangeReader::new(reader).collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(ranges, [(1, 2), (3, 4), (5, 6), (u128::MAX - 1, u128::MAX)]);
+    }
+
+    #[test]
+    fn reports_overlapping_ranges() {
+        let input = "1-100,200-300\n150-160,90-210,400-500,160-160";
+        let mut reader = RangeReader::new(input.as_bytes()).track_overlaps();
+        assert_eq!(reader.by_ref().count(), 6);
 
-        ranges.push((start, end));
+        assert_eq!(reader.overlaps(), [
+            Overlap { line: 2, range: (90, 210) },
+            Overlap { line: 2, range: (160, 160) },
+        ]);
+
+        let mut untracked = RangeReader::new(input.as_bytes());
+        assert_eq!(untracked.by_ref().count(), 6);
+        assert!(untracked.overlaps().is_empty());
     }
 
-    Ok(ranges)
-}
\ No newline at end of file
+    #[test]
+    fn rejects_invalid_ranges() {
+        let mut reader = RangeReader::new("1-2\n7-3,4-5".as_bytes());
+        assert!(matches!(reader.next(), Some(Ok((1, 2)))));
+
+        let err = reader.next().unwrap().unwrap_err();
+        assert!(err.to_string().starts_with("Parse error: Range 7-3 ends before it starts\n --> <input>:2:1"), "{err}");
+        assert!(reader.next().is_none());
+
+        assert!(solve_01("1-x").is_err());
+        assert!(solve_01("12").is_err());
+    }
+}
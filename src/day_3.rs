@@ -1,120 +1,441 @@
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 
-const TASK_VERSION: u8 = 2;
 const BATTERIES_NEEDED: usize = 12;
 
-pub fn batteries(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => batteries_01(path),
-        2 => batteries_02(path),
-        _ => Err("Invalid task version!".into()),
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        3
+    }
+
+    fn title(&self) -> &'static str {
+        "Lobby"
     }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        input.lines()
+            .enumerate()
+            .try_for_each(|(line_idx, line)| parse_bank(line_idx + 1, line).map(drop))
+    }
+
+    fn explain(&self, part: u8, input: &str) -> Option<Result<String, Error>> {
+        let k = match part {
+            1 => 2,
+            2 => BATTERIES_NEEDED,
+            _ => return None,
+        };
+        Some(breakdown(input, k).map(|breakdown| breakdown.to_string()))
+    }
+}
+
+/// Solve part one for the puzzle input file at `path`
+pub fn batteries_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
 }
 
-pub fn batteries_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+/// Solve part two for the puzzle input file at `path`
+pub fn batteries_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-    let mut result = 0;
-    for line in input.lines() {
-        let batteries_joltage: Vec<u32> = line
-            .chars()
-            .map(|char| char.to_digit(10).ok_or_else(|| format!("Invalid digit: {}", char)))
-            .collect::<Result<Vec<u32>, String>>()?;
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    total_joltage(input, 2)
+}
 
-        if batteries_joltage.len() < 2 {
-            return Err("Not enough batteries to calculate voltage".into());
-        }
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    total_joltage(input, BATTERIES_NEEDED)
+}
 
-        // Finding first maximum
-        let mut max = 0;
-        let mut idx = 0;
-        for joltage in batteries_joltage.iter().enumerate() {
-            if *joltage.1 > max {
-                idx = joltage.0;
-                max = *joltage.1;
-            }
-        }
+/// Sum of the largest joltage of every bank with `k` batteries switched on
+fn total_joltage(input: &str, k: usize) -> Result<u64, Error> {
+    let mut sum: u64 = 0;
+    for (line_idx, line) in input.lines().enumerate() {
+        let joltage = max_joltage(&parse_bank(line_idx + 1, line)?, k)?;
+
+        let value = joltage.value()
+            .and_then(|value| u64::try_from(value).ok())
+            .ok_or_else(|| Error::SolverLimit(format!("Joltage {joltage} does not fit into u64")))?;
+        sum = sum.checked_add(value).ok_or_else(|| Error::SolverLimit("Total joltage does not fit into u64".into()))?;
+    }
 
-        let is_last = idx == batteries_joltage.len() - 1;
+    Ok(sum)
+}
 
-        let batteries_slice = if is_last { &batteries_joltage[..idx] } else { &batteries_joltage[idx+1..] };
+/// Batteries picked in every bank of the input with `k` switched on
+pub fn breakdown(input: &str, k: usize) -> Result<Breakdown, Error> {
+    let banks = input.lines()
+        .enumerate()
+        .map(|(line_idx, line)| {
+            let joltage = max_joltage(&parse_bank(line_idx + 1, line)?, k)?;
+            Ok(BankReport { line: line_idx + 1, bank: line.to_string(), joltage })
+        })
+        .collect::<Result<_, Error>>()?;
 
-        let mut second_max = 0;
-        for joltage in batteries_slice.iter().enumerate() {
-            if *joltage.1 > second_max {
-                second_max = *joltage.1;
+    Ok(Breakdown { banks })
+}
+
+/// Every bank's share of the total joltage, to check an answer bank by bank
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakdown {
+    pub banks: Vec<BankReport>,
+}
+
+/// Batteries picked in one bank of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankReport {
+    /// Line of the input, 1-based
+    pub line: usize,
+    pub bank: String,
+    pub joltage: Joltage,
+}
+
+impl Breakdown {
+    /// Sum over every bank, `None` if it does not fit into u128
+    pub fn total(&self) -> Option<u128> {
+        self.banks.iter().try_fold(0u128, |total, bank| total.checked_add(bank.joltage.value()?))
+    }
+}
+
+/// One bank per line with its joltage, the picked batteries marked underneath, and the total:
+///
+/// ```text
+/// 1 | 818181911112111 = 92
+///   | ^         ^
+/// Total: 92
+/// ```
+impl fmt::Display for Breakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = self.banks.last().map_or(1, |bank| bank.line.to_string().len());
+
+        for bank in &self.banks {
+            writeln!(f, "{:>gutter$} | {} = {}", bank.line, bank.bank, bank.joltage)?;
+
+            let mut marks = vec![' '; bank.joltage.indices.last().map_or(0, |&last| last + 1)];
+            for &idx in &bank.joltage.indices {
+                marks[idx] = '^';
             }
+            writeln!(f, "{:gutter$} | {}", "", marks.into_iter().collect::<String>())?;
         }
 
-        // Edge case - maximum is last item
-        // Swap the maximums
-        if is_last {
-            let tmp = max;
-            max = second_max;
-            second_max = tmp;
+        match self.total() {
+            Some(total) => write!(f, "Total: {total}"),
+            None => write!(f, "Total: does not fit into u128"),
         }
+    }
+}
 
-        // Create two digit number
-        let found_voltage = (max.to_string() + &second_max.to_string()).parse::<u64>().unwrap();
-        result += found_voltage;
+/// Batteries switched on in a bank and the joltage they produce
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Joltage {
+    /// Positions of the chosen batteries within the bank, ascending
+    pub indices: Vec<usize>,
+    /// Joltage digits of the chosen batteries, in bank order
+    pub digits: Vec<u32>,
+}
+
+impl Joltage {
+    /// The digits read as one number, `None` if it does not fit into u128 (more than 38 digits).
+    /// [`Display`](fmt::Display) writes it out whatever the length.
+    pub fn value(&self) -> Option<u128> {
+        self.digits.iter().try_fold(0u128, |value, &digit| value.checked_mul(10)?.checked_add(u128::from(digit)))
     }
+}
 
-    Ok(result)
+impl fmt::Display for Joltage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.digits.iter().try_for_each(|digit| write!(f, "{digit}"))
+    }
 }
 
-pub fn batteries_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+/// Largest joltage from switching on exactly `k` batteries of `bank`, keeping their order
+/// and preferring the earliest batteries among equal choices.
+///
+/// Runs in O(n) with a monotonic stack: a battery knocks out the weaker ones picked before it
+/// for as long as there are batteries left to skip, since a larger earlier digit always wins.
+pub fn max_joltage(bank: &[u32], k: usize) -> Result<Joltage, Error> {
+    select_monotonic(bank, k, |picked, digit| picked < digit)
+}
 
-    let mut sum: u64 = 0;
-    for line in input.lines() {
-        if line.len() < BATTERIES_NEEDED {
-            return Err("Not enough batteries".into());
+/// Smallest joltage from switching on exactly `k` batteries of `bank`, the mirror image of [`max_joltage`]
+pub fn min_joltage(bank: &[u32], k: usize) -> Result<Joltage, Error> {
+    select_monotonic(bank, k, |picked, digit| picked > digit)
+}
+
+/// Largest joltage from `k` batteries of `bank` leaving the batteries at `forbidden` switched off
+pub fn max_joltage_avoiding(bank: &[u32], k: usize, forbidden: &[usize]) -> Result<Joltage, Error> {
+    let allowed: Vec<usize> = (0..bank.len()).filter(|idx| !forbidden.contains(idx)).collect();
+    let usable: Vec<u32> = allowed.iter().map(|&idx| bank[idx]).collect();
+
+    let joltage = max_joltage(&usable, k)?;
+    Ok(Joltage { indices: joltage.indices.iter().map(|&idx| allowed[idx]).collect(), digits: joltage.digits })
+}
+
+/// Largest joltage from `k` batteries of `bank` with at most `max_skip` batteries left off
+/// between two batteries switched on. Batteries before the first and after the last pick are free to skip.
+///
+/// The greedy choice no longer works, as the earliest large digit may strand the picks after it
+/// among small ones. Instead the best `r` battery suffix starting at every battery is ranked against
+/// the others for `r = 1..=k`, each extending the best-ranked suffix within reach.
+/// Sliding that reach along the bank as a monotonic queue keeps the cost independent of `max_skip`,
+/// O(k·n·log n) time and O(k·n) memory.
+pub fn max_joltage_with_gaps(bank: &[u32], k: usize, max_skip: usize) -> Result<Joltage, Error> {
+    check_enough(bank, k)?;
+    let n = bank.len();
+    if k == 0 {
+        return Ok(Joltage { indices: Vec::new(), digits: Vec::new() });
+    }
+
+    // rank[i] orders the best picks of r batteries starting at i, None where fewer than r batteries are left
+    let mut rank: Vec<Option<usize>> = bank.iter().map(|&digit| Some(digit as usize)).collect();
+    // next[r - 2][i] is the battery picked after i in the best pick of r batteries starting at i
+    let mut next: Vec<Vec<usize>> = Vec::with_capacity(k - 1);
+
+    for r in 2..=k {
+        let mut successor = vec![usize::MAX; n];
+        let mut keys: Vec<Option<(u32, usize)>> = vec![None; n];
+        // Batteries within reach of idx with their rank, by position. A battery outranked by one before it
+        // never becomes the best pick again, as it leaves the reach first, so the ranks rise towards the back.
+        let mut reachable: VecDeque<(usize, usize)> = VecDeque::new();
+
+        for idx in (0..=n - r).rev() {
+            if let Some(next_rank) = rank[idx + 1] {
+                while reachable.front().is_some_and(|&(_, other_rank)| other_rank <= next_rank) {
+                    reachable.pop_front();
+                }
+                reachable.push_front((idx + 1, next_rank));
+            }
+
+            let reach = idx.saturating_add(max_skip).saturating_add(1);
+            while reachable.back().is_some_and(|&(next_idx, _)| next_idx > reach) {
+                reachable.pop_back();
+            }
+
+            // Ranks within reach are distinct, so the best is also the earliest among equally good successors
+            if let Some(&(next_idx, next_rank)) = reachable.back() {
+                successor[idx] = next_idx;
+                keys[idx] = Some((bank[idx], next_rank));
+            }
         }
 
-        let best = max_subsequence(line, BATTERIES_NEEDED);
+        let mut sorted: Vec<(u32, usize)> = keys.iter().flatten().copied().collect();
+        sorted.sort_unstable();
+        sorted.dedup();
+        rank = keys.iter().map(|key| key.map(|key| sorted.partition_point(|&other| other < key))).collect();
+        next.push(successor);
+    }
 
-        let value = best.parse::<u64>()
-            .map_err(|e| format!("Parse error: {e}"))?;
+    let first = (0..n)
+        .filter_map(|idx| rank[idx].map(|rank| (rank, Reverse(idx))))
+        .max()
+        .map(|(_, Reverse(idx))| idx)
+        .expect("every battery up to n - k reaches the one right after it");
 
-        sum += value;
+    let mut indices = vec![first];
+    for successor in next.iter().rev() {
+        indices.push(successor[indices[indices.len() - 1]]);
     }
 
-    Ok(sum)
+    Ok(Joltage { digits: indices.iter().map(|&idx| bank[idx]).collect(), indices })
+}
+
+/// Pick `k` batteries with a monotonic stack, a new battery replacing the last pick while `replaces(last, new)`
+fn select_monotonic(bank: &[u32], k: usize, replaces: impl Fn(u32, u32) -> bool) -> Result<Joltage, Error> {
+    check_enough(bank, k)?;
+
+    let mut skips = bank.len() - k;
+    let mut chosen: Vec<usize> = Vec::with_capacity(bank.len());
+    for (idx, &digit) in bank.iter().enumerate() {
+        while skips > 0 && chosen.last().is_some_and(|&last| replaces(bank[last], digit)) {
+            chosen.pop();
+            skips -= 1;
+        }
+        chosen.push(idx);
+    }
+    // Skips left over are spent on the tail
+    chosen.truncate(k);
+
+    Ok(Joltage { digits: chosen.iter().map(|&idx| bank[idx]).collect(), indices: chosen })
 }
 
-fn max_subsequence(line: &str, k: usize) -> String {
-    let chars: Vec<char> = line.chars().collect();
-    let n = chars.len();
+fn check_enough(bank: &[u32], k: usize) -> Result<(), Error> {
+    if bank.len() < k {
+        return Err(Error::InvalidInput(format!("Not enough batteries, {k} needed but the bank has {}", bank.len())));
+    }
+    Ok(())
+}
 
-    let mut result = String::with_capacity(k);
-    let mut start = 0;
+/// Parse a bank of batteries, one joltage digit per battery
+pub fn parse_bank(line_no: usize, line: &str) -> Result<Vec<u32>, Error> {
+    line.chars()
+        .enumerate()
+        .map(|(idx, char)| char.to_digit(10).ok_or_else(|| Error::parse(line_no, line, idx + 1, format!("Invalid digit: {}", char))))
+        .collect()
+}
 
-    for remaining in (1..=k).rev() {
-        // The last index where we can pick the next digit
-        let end = n - remaining;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Rng;
 
-        // Find the max digit in chars[start..=end]
-        let mut max_digit = '0';
-        let mut max_idx = start;
+    const EXAMPLE: &str = "\
+987654321111111
+811111111111119
+234234234234278
+818181911112111
+";
 
-        for i in start..=end {
-            if chars[i] > max_digit {
-                max_digit = chars[i];
-                max_idx = i;
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 357);
+    }
 
-                // Early stop if we found '9'
-                if max_digit == '9' {
-                    break;
-                }
-            }
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 3121910778619);
+    }
+
+    #[test]
+    fn breaks_the_total_down_by_bank() {
+        let breakdown = breakdown(EXAMPLE, 2).unwrap();
+
+        assert_eq!(breakdown.total(), Some(357));
+        assert_eq!(breakdown.banks[3].joltage.indices, [6, 11]);
+        assert_eq!(breakdown.to_string(), "\
+1 | 987654321111111 = 98
+  | ^^
+2 | 811111111111119 = 89
+  | ^             ^
+3 | 234234234234278 = 78
+  |              ^^
+4 | 818181911112111 = 92
+  |       ^    ^
+Total: 357");
+        assert_eq!(Puzzle.explain(1, "1119\n").unwrap().unwrap(), "1 | 1119 = 19\n  | ^  ^\nTotal: 19");
+        assert!(Puzzle.explain(2, "12\n").unwrap().is_err());
+    }
+
+    #[test]
+    fn part_1_maximum_at_the_end() {
+        assert_eq!(solve_01("1119").unwrap(), 19);
+    }
+
+    /// Every choice of `k` batteries as `(indices, digits)`
+    fn choices(line: &str, k: usize) -> Vec<(Vec<usize>, String)> {
+        let chars: Vec<char> = line.chars().collect();
+        (0u32..1 << chars.len())
+            .filter(|mask| mask.count_ones() as usize == k)
+            .map(|mask| {
+                let indices: Vec<usize> = (0..chars.len()).filter(|i| mask & (1 << i) != 0).collect();
+                let digits = indices.iter().map(|&i| chars[i]).collect();
+                (indices, digits)
+            })
+            .collect()
+    }
+
+    /// Best subsequence by trying every subset of `k` batteries
+    fn brute_force(line: &str, k: usize) -> String {
+        choices(line, k).into_iter().map(|(_, digits)| digits).max().unwrap()
+    }
+
+    fn random_bank(rng: &mut Rng) -> (String, usize) {
+        let n = rng.range(1..=10) as usize;
+        let line: String = (0..n).map(|_| char::from(b'0' + rng.range(0..=9) as u8)).collect();
+        (line, rng.range(1..=n as u64) as usize)
+    }
+
+    #[test]
+    fn variants_match_brute_force() {
+        let mut rng = Rng::new(22);
+
+        for _ in 0..500 {
+            let (line, k) = random_bank(&mut rng);
+            let bank = parse_bank(1, &line).unwrap();
+            let all = choices(&line, k);
+
+            let min = min_joltage(&bank, k).unwrap();
+            assert_eq!(Some(min.to_string()), all.iter().map(|(_, digits)| digits.clone()).min(), "min {line} {k}");
+
+            let forbidden = [rng.range(0..=9) as usize, rng.range(0..=9) as usize];
+            let best = all.iter()
+                .filter(|(indices, _)| indices.iter().all(|idx| !forbidden.contains(idx)))
+                .map(|(_, digits)| digits.clone())
+                .max();
+            let avoiding = max_joltage_avoiding(&bank, k, &forbidden).ok();
+            assert_eq!(avoiding.as_ref().map(Joltage::to_string), best, "avoiding {forbidden:?} {line} {k}");
+            assert!(avoiding.is_none_or(|joltage| joltage.indices.iter().all(|idx| !forbidden.contains(idx))));
+
+            let max_skip = rng.range(0..=3) as usize;
+            let best = all.iter()
+                .filter(|(indices, _)| indices.windows(2).all(|pair| pair[1] - pair[0] - 1 <= max_skip))
+                .map(|(_, digits)| digits.clone())
+                .max();
+            let with_gaps = max_joltage_with_gaps(&bank, k, max_skip).unwrap();
+            assert_eq!(Some(with_gaps.to_string()), best, "max skip {max_skip} {line} {k}");
+            assert!(with_gaps.indices.windows(2).all(|pair| pair[1] - pair[0] - 1 <= max_skip));
+            assert_eq!(with_gaps.indices.iter().map(|&idx| bank[idx]).collect::<Vec<_>>(), with_gaps.digits);
         }
+    }
+
+    #[test]
+    fn gaps_can_rule_out_the_greedy_pick() {
+        let bank = parse_bank(1, "9199").unwrap();
+
+        assert_eq!(max_joltage(&bank, 2).unwrap().indices, [0, 2]);
+        assert_eq!(max_joltage_with_gaps(&bank, 2, 0).unwrap().indices, [2, 3]);
+        assert_eq!(max_joltage_with_gaps(&bank, 2, usize::MAX).unwrap().indices, [0, 2]);
+        assert_eq!(min_joltage(&bank, 2).unwrap().to_string(), "19");
+        assert_eq!(max_joltage_avoiding(&bank, 2, &[0, 2]).unwrap().indices, [1, 3]);
+        assert!(max_joltage_avoiding(&bank, 3, &[0, 2]).is_err());
+    }
+
+    #[test]
+    fn max_joltage_matches_brute_force() {
+        let mut rng = Rng::new(3);
+
+        for _ in 0..500 {
+            let n = rng.range(1..=10) as usize;
+            let line: String = (0..n).map(|_| char::from(b'1' + rng.range(0..=8) as u8)).collect();
+            let k = rng.range(1..=n as u64) as usize;
 
-        // Append it
-        result.push(max_digit);
+            let bank = parse_bank(1, &line).unwrap();
+            let best = max_joltage(&bank, k).unwrap();
 
-        // Move start after the chosen digit
-        start = max_idx + 1;
+            assert_eq!(best.to_string(), brute_force(&line, k), "line {line}, k {k}");
+            assert_eq!(best.indices.len(), k);
+            assert!(best.indices.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(best.indices.iter().zip(&best.digits).all(|(&idx, &digit)| bank[idx] == digit));
+        }
     }
 
-    result
-}
\ No newline at end of file
+    #[test]
+    fn max_joltage_beyond_u128() {
+        let bank = parse_bank(1, &"1234567890".repeat(5)).unwrap();
+
+        let joltage = max_joltage(&bank, 2).unwrap();
+        assert_eq!(joltage.indices, [8, 18]);
+        assert_eq!(joltage.value(), Some(99));
+
+        let joltage = max_joltage(&bank, 45).unwrap();
+        assert_eq!(joltage.value(), None);
+        assert_eq!(joltage.to_string(), "678901234567890123456789012345678901234567890");
+
+        assert!(matches!(max_joltage(&bank, 51), Err(Error::InvalidInput(_))));
+    }
+}
@@ -1,136 +1,354 @@
-use crate::utils::read_file;
+use std::collections::VecDeque;
+use std::fs;
 
-const TASK_VERSION: u8 = 2;
+use crate::error::Error;
+use crate::solution::Solution;
+use crate::utils::{ read_file, Grid };
 
-pub fn rolls_of_paper(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => rolls_of_paper_01(path),
-        2 => rolls_of_paper_02(path),
-        _ => Err("Invalid task version!".into()),
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        4
+    }
+
+    fn title(&self) -> &'static str {
+        "Printing Department"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        Grid::parse(input).map(drop)
+    }
+
+    fn explain(&self, part: u8, input: &str) -> Option<Result<String, Error>> {
+        (part == 2).then(|| {
+            let timeline = removal_timeline(&Grid::parse(input)?);
+            let removed = timeline.removed_per_round();
+
+            let mut lines: Vec<String> = removed
+                .iter()
+                .enumerate()
+                .map(|(idx, removed)| format!("Round {}: {removed} removed", idx + 1))
+                .collect();
+            lines.push(format!("Total: {}", removed.iter().sum::<u64>()));
+            Ok(lines.join("\n"))
+        })
     }
+
+    fn write_frames(&self, part: u8, input: &str, dir: &str, format: FrameFormat) -> Option<Result<(), Error>> {
+        (part == 2).then(|| removal_timeline(&Grid::parse(input)?).write_frames(dir, format))
+    }
+}
+
+/// Solve part one for the puzzle input file at `path`
+pub fn rolls_of_paper_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
 }
 
-pub fn rolls_of_paper_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+/// Solve part two for the puzzle input file at `path`
+pub fn rolls_of_paper_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-    // Borrow each line as a byte slice
-    let grid: Vec<&[u8]> = input
-        .lines()
-        .map(|line| line.as_bytes())
-        .collect();
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    let grid = Grid::parse(input)?;
 
     if grid.is_empty() {
-        return Err("Input is empty".to_string());
+        return Err(Error::InvalidInput("Input is empty".into()));
     }
 
-    let rows = grid.len();
-    let cols = grid[0].len();
+    let accessible_count = grid
+        .positions()
+        .filter(|&(row, col)| grid[(row, col)] == b'@' && adjacent_rolls(&grid, row, col) < 4)
+        .count();
 
-    let mut accessible_count = 0;
+    Ok(accessible_count as u64)
+}
 
-    for row in 0..rows {
-        for col in 0..cols {
-            if grid[row][col] != b'@' {
-                continue;
-            }
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    let mut grid = Grid::parse(input)?;
+
+    if grid.is_empty() {
+        return Err(Error::InvalidInput("Input is empty".into()));
+    }
+
+    Ok(remove_accessible(&mut grid))
+}
+
+/// Keep removing accessible rolls from `grid` until none is left, returning how many were removed
+pub fn remove_accessible(grid: &mut Grid<u8>) -> u64 {
+    removal_rounds(grid).rows().flatten().filter(|round| round.is_some()).count() as u64
+}
+
+/// Remove every roll that becomes accessible, all rolls accessible at the start of a round going at once,
+/// and record the round each one went in
+pub fn removal_timeline(grid: &Grid<u8>) -> Timeline {
+    let removed_in = removal_rounds(&mut grid.clone());
+    Timeline { start: grid.clone(), removed_in }
+}
+
+/// Empty `grid` of accessible rolls, returning the round, from 1, each roll was removed in.
+///
+/// Neighbour counts are taken once up front. Removing a roll only lowers the counts around it,
+/// so a roll is queued exactly when its count drops below 4 and no cell is ever rescanned,
+/// which makes the whole removal linear in the size of the grid.
+/// A roll freed by a removal in round `r` is accessible from round `r + 1` on, and as the queue
+/// hands out rolls round by round, the rounds come out the same as when removing in sweeps.
+fn removal_rounds(grid: &mut Grid<u8>) -> Grid<Option<u32>> {
+    let mut counts = Grid::filled(grid.width(), grid.height(), 0usize);
+    let mut removed_in = Grid::filled(grid.width(), grid.height(), None);
+    let mut queue = VecDeque::new();
+
+    for position in grid.positions().filter(|&position| grid[position] == b'@') {
+        let (row, col) = position;
+        counts[position] = adjacent_rolls(grid, row, col);
+        if counts[position] < 4 {
+            queue.push_back((position, 1));
+        }
+    }
+
+    while let Some(((row, col), round)) = queue.pop_front() {
+        grid[(row, col)] = b'.';
+        removed_in[(row, col)] = Some(round);
 
-            let mut adjacent_rolls = 0;
-
-            // Explore 8 directions around (row, col)
-            for d_row in -1i32..=1 {
-                for d_col in -1i32..=1 {
-                    if d_row == 0 && d_col == 0 {
-                        continue; // skip the tile itself
-                    }
-
-                    let nr = row as i32 + d_row; // neighbor row
-                    let nc = col as i32 + d_col; // neighbor col
-
-                    // Check bounds
-                    if nr >= 0 && nr < rows as i32 &&
-                       nc >= 0 && nc < cols as i32 &&
-                       grid[nr as usize][nc as usize] == b'@'
-                    {
-                        adjacent_rolls += 1;
-                    }
+        for neighbour in grid.neighbours8(row, col) {
+            if grid[neighbour] == b'@' {
+                counts[neighbour] -= 1;
+                // Queued once, when the count first drops below 4
+                if counts[neighbour] == 3 {
+                    queue.push_back((neighbour, round + 1));
                 }
             }
+        }
+    }
 
-            if adjacent_rolls < 4 {
-                accessible_count += 1;
+    removed_in
+}
+
+/// Image format of exported frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// The grid as text, rolls removed in the round marked `x`
+    Text,
+    /// Binary greyscale image, `scale` pixels per cell
+    Pgm { scale: usize },
+}
+
+/// How a grid of rolls was emptied, round by round
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeline {
+    /// The grid before the first removal
+    pub start: Grid<u8>,
+    /// Round each roll was removed in, from 1, `None` for free cells and rolls that are never removed
+    pub removed_in: Grid<Option<u32>>,
+}
+
+impl Timeline {
+    /// Number of rounds that removed at least one roll
+    pub fn rounds(&self) -> u32 {
+        self.removed_in.rows().flatten().flatten().copied().max().unwrap_or(0)
+    }
+
+    /// Rolls removed in each round, starting with round 1
+    pub fn removed_per_round(&self) -> Vec<u64> {
+        let mut removed = vec![0; self.rounds() as usize];
+        for &round in self.removed_in.rows().flatten().flatten() {
+            removed[round as usize - 1] += 1;
+        }
+        removed
+    }
+
+    /// The grid during `round`, rolls removed in it marked `x` and those removed before gone.
+    /// Round 0 is the starting grid.
+    pub fn frame(&self, round: u32) -> Grid<u8> {
+        let mut frame = self.start.clone();
+        for position in frame.positions() {
+            match self.removed_in[position] {
+                Some(removed) if removed == round => frame[position] = b'x',
+                Some(removed) if removed < round => frame[position] = b'.',
+                _ => {},
             }
         }
+        frame
     }
 
-    Ok(accessible_count)
-}
+    /// Every frame from round 0 to the last round, each under a `Round N` header
+    pub fn to_text(&self) -> String {
+        let removed = self.removed_per_round();
 
-pub fn rolls_of_paper_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+        (0..=self.rounds())
+            .map(|round| match round {
+                0 => format!("Round 0\n{}\n", self.start),
+                _ => format!("Round {round}, {} removed\n{}\n", removed[round as usize - 1], self.frame(round)),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 
-    // We need mutability now because we remove '@' rolls
-    let mut grid: Vec<Vec<u8>> = input
-        .lines()
-        .map(|line| line.as_bytes().to_vec())
-        .collect();
+    /// Binary PGM image of `frame(round)` with `scale` pixels per cell:
+    /// rolls black, rolls removed in the round grey and free cells white
+    pub fn frame_pgm(&self, round: u32, scale: usize) -> Vec<u8> {
+        let frame = self.frame(round);
+        let scale = scale.max(1);
 
-    if grid.is_empty() {
-        return Err("Input is empty".to_string());
+        let mut image = format!("P5\n{} {}\n255\n", frame.width() * scale, frame.height() * scale).into_bytes();
+        for row in frame.rows() {
+            let pixels: Vec<u8> = row
+                .iter()
+                .flat_map(|&cell| {
+                    let shade = match cell {
+                        b'@' => 0,
+                        b'x' => 128,
+                        _ => 255,
+                    };
+                    std::iter::repeat_n(shade, scale)
+                })
+                .collect();
+
+            for _ in 0..scale {
+                image.extend_from_slice(&pixels);
+            }
+        }
+        image
     }
 
-    let rows = grid.len();
-    let cols = grid[0].len();
+    /// Write every frame into `dir` as `frame_000.txt` or `frame_000.pgm` onwards, creating `dir` if needed
+    pub fn write_frames(&self, dir: &str, format: FrameFormat) -> Result<(), Error> {
+        fs::create_dir_all(dir).map_err(|source| Error::Io { path: dir.to_string(), source })?;
 
-    // Offsets for all 8 neighbors
-    let directions = [
-        (-1, -1), (-1, 0), (-1, 1),
-        ( 0, -1),          ( 0, 1),
-        ( 1, -1), ( 1, 0), ( 1, 1),
-    ];
+        for round in 0..=self.rounds() {
+            let (extension, contents) = match format {
+                FrameFormat::Text => ("txt", format!("{}\n", self.frame(round)).into_bytes()),
+                FrameFormat::Pgm { scale } => ("pgm", self.frame_pgm(round, scale)),
+            };
 
-    let mut removed_total = 0;
+            let path = format!("{dir}/frame_{round:03}.{extension}");
+            fs::write(&path, contents).map_err(|source| Error::Io { path: path.clone(), source })?;
+        }
 
-    loop {
-        let mut to_remove = Vec::new();
+        Ok(())
+    }
+}
 
-        // Scan grid for removable rolls
-        for row in 0..rows {
-            for col in 0..cols {
-                if grid[row][col] != b'@' {
-                    continue;
-                }
+/// Number of rolls in the 8 cells around (row, col)
+fn adjacent_rolls(grid: &Grid<u8>, row: usize, col: usize) -> usize {
+    grid.neighbours8(row, col).filter(|&position| grid[position] == b'@').count()
+}
 
-                let mut neighbors = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ temp_dir, Rng };
+    use std::path::Path;
 
-                for (dr, dc) in directions {
-                    let nr = row as i32 + dr;
-                    let nc = col as i32 + dc;
+    const EXAMPLE: &str = "\
+..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.
+";
 
-                    if nr >= 0 && nr < rows as i32 &&
-                       nc >= 0 && nc < cols as i32 &&
-                       grid[nr as usize][nc as usize] == b'@'
-                    {
-                        neighbors += 1;
-                    }
-                }
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 13);
+    }
 
-                if neighbors < 4 {
-                    to_remove.push((row, col));
-                }
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 43);
+    }
+
+    /// Removal round by round, rescanning the whole grid each time, with the round of every removal
+    fn remove_by_rescanning(grid: &mut Grid<u8>) -> Grid<Option<u32>> {
+        let mut removed_in = Grid::filled(grid.width(), grid.height(), None);
+
+        for round in 1.. {
+            let to_remove: Vec<(usize, usize)> = grid
+                .positions()
+                .filter(|&(row, col)| grid[(row, col)] == b'@' && adjacent_rolls(grid, row, col) < 4)
+                .collect();
+
+            if to_remove.is_empty() {
+                break;
             }
-        }
 
-        // Stop when nothing more is removable
-        if to_remove.is_empty() {
-            break;
+            for position in to_remove {
+                grid[position] = b'.';
+                removed_in[position] = Some(round);
+            }
         }
 
-        // Remove all marked rolls
-        for (row, col) in to_remove {
-            grid[row][col] = b'.';
-            removed_total += 1;
+        removed_in
+    }
+
+    #[test]
+    fn queue_matches_rescanning() {
+        let mut rng = Rng::new(4);
+
+        for _ in 0..100 {
+            let (width, height) = (rng.range(1..=20) as usize, rng.range(1..=20) as usize);
+            let density = rng.range(1..=9);
+            let rows = (0..height)
+                .map(|_| (0..width).map(|_| if rng.range(0..=9) < density { b'@' } else { b'.' }).collect())
+                .collect();
+            let grid = Grid::from_rows(rows).unwrap();
+
+            let timeline = removal_timeline(&grid);
+            let (mut queued, mut rescanned) = (grid.clone(), grid);
+            assert_eq!(timeline.removed_in, remove_by_rescanning(&mut rescanned));
+            assert_eq!(remove_accessible(&mut queued), timeline.removed_per_round().iter().sum::<u64>());
+            assert_eq!(queued, rescanned);
         }
     }
 
-    Ok(removed_total)
+    #[test]
+    fn records_removal_rounds() {
+        let timeline = removal_timeline(&Grid::parse(EXAMPLE).unwrap());
+
+        assert_eq!(timeline.removed_per_round(), [13, 12, 7, 5, 2, 1, 1, 1, 1]);
+        assert_eq!(timeline.frame(0), timeline.start);
+        assert_eq!(timeline.frame(1).to_string().lines().next(), Some("..xx.xx@x."));
+        assert_eq!(timeline.frame(2).to_string().lines().nth(1), Some(".@@.x.x.@x"));
+        assert!(timeline.to_text().starts_with("Round 0\n..@@.@@@@.\n"));
+        assert!(timeline.to_text().contains("\n\nRound 9, 1 removed\n"));
+        assert_eq!(Puzzle.explain(2, EXAMPLE).unwrap().unwrap().lines().last(), Some("Total: 43"));
+    }
+
+    #[test]
+    fn exports_frames() {
+        let timeline = removal_timeline(&Grid::parse("@@\n.@\n").unwrap());
+
+        assert_eq!(timeline.frame_pgm(1, 2), b"P5\n4 4\n255\n\x80\x80\x80\x80\x80\x80\x80\x80\xff\xff\x80\x80\xff\xff\x80\x80");
+
+        let dir = temp_dir("frames");
+        let dir = dir.to_string_lossy();
+        timeline.write_frames(&dir, FrameFormat::Text).unwrap();
+        timeline.write_frames(&dir, FrameFormat::Pgm { scale: 1 }).unwrap();
+
+        assert_eq!(fs::read_to_string(format!("{dir}/frame_000.txt")).unwrap(), "@@\n.@\n");
+        assert_eq!(fs::read_to_string(format!("{dir}/frame_001.txt")).unwrap(), "xx\n.x\n");
+        assert_eq!(fs::read(format!("{dir}/frame_000.pgm")).unwrap(), b"P5\n2 2\n255\n\x00\x00\xff\x00");
+        assert!(!Path::new(&format!("{dir}/frame_002.txt")).exists());
+    }
+
+    #[test]
+    fn rejects_ragged_grid() {
+        assert!(matches!(solve_01("@@@\n@@\n"), Err(Error::Parse(_))));
+    }
 }
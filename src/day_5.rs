@@ -1,19 +1,44 @@
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 
-const TASK_VERSION: u8 = 2;
+pub struct Puzzle;
 
-pub fn ingredients(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => ingredients_01(path),
-        2 => ingredients_02(path),
-        _ => Err("Invalid task version!".into()),
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        5
+    }
+
+    fn title(&self) -> &'static str {
+        "Cafeteria"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        parse_input(input).map(drop)
     }
 }
 
-pub fn ingredients_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+/// Solve part one for the puzzle input file at `path`
+pub fn ingredients_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part two for the puzzle input file at `path`
+pub fn ingredients_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
-    let parsed_input = parse_input(&input)?;
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    let parsed_input = parse_input(input)?;
     let ranges = parsed_input.0;
     let ingredients = parsed_input.1;
 
@@ -27,10 +52,9 @@ pub fn ingredients_01(path: &str) -> Result<u64, String> {
     Ok(result)
 }
 
-pub fn ingredients_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-
-    let parsed_input = parse_input(&input)?;
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    let parsed_input = parse_input(input)?;
     let ranges = parsed_input.0;
 
     // Merge ranges - take spaces into account
@@ -46,23 +70,26 @@ pub fn ingredients_02(path: &str) -> Result<u64, String> {
     Ok(result)
 }
 
-fn parse_input(input: &str) -> Result<(Vec<(u64, u64)>, Vec<u64>), String> {
+type ParsedInput = (Vec<(u64, u64)>, Vec<u64>);
+
+fn parse_input(input: &str) -> Result<ParsedInput, Error> {
     let mut ranges = Vec::new();
     let mut ingredients = Vec::new();
-    for line in input.lines() {
-        if line.contains('-') {
-            let mut range = line.split('-');
-            ranges.push(
-                (
-                    range.next().ok_or("Wrong input!")?.parse::<u64>().map_err(|err| format!("Wrong input {err}"))?,
-                    range.next().ok_or("Wrong input!")?.parse::<u64>().map_err(|err| format!("Wrong input {err}"))?
-                )
-            )
-        }
-        else {
-            if line.len() > 0 {
-                ingredients.push(line.parse::<u64>().map_err(|err| format!("Wrong input {err}"))?);
+    for (line_idx, line) in input.lines().enumerate() {
+        let line_no = line_idx + 1;
+        let parse_id = |token: &str| {
+            token.parse::<u64>().map_err(|err| Error::parse_at(line_no, line, token, format!("Wrong input {err}")))
+        };
+
+        if let Some((start, end)) = line.split_once('-') {
+            let range = (parse_id(start)?, parse_id(end)?);
+            if range.0 > range.1 {
+                return Err(Error::parse_at(line_no, line, line, "Range start is greater than its end"));
             }
+            ranges.push(range);
+        }
+        else if !line.is_empty() {
+            ingredients.push(parse_id(line)?);
         }
     }
 
@@ -70,16 +97,20 @@ fn parse_input(input: &str) -> Result<(Vec<(u64, u64)>, Vec<u64>), String> {
 }
 
 fn merge_ranges(ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    if ranges.is_empty() {
+        return ranges;
+    }
+
     let mut merged_ranges: Vec<(u64, u64)> = vec![ranges[0]];
     for new_range in &ranges[1..] {
         let mut overlaps = false;
-        for i in 0..merged_ranges.len() {
-            if do_ranges_overlap(new_range, &merged_ranges[i]) {
+        for merged_range in merged_ranges.iter_mut() {
+            if do_ranges_overlap(new_range, merged_range) {
                 overlaps = true;
-                let new_start = new_range.0.min(merged_ranges[i].0);
-                let new_end = new_range.1.max(merged_ranges[i].1);
+                let new_start = new_range.0.min(merged_range.0);
+                let new_end = new_range.1.max(merged_range.1);
                 
-                merged_ranges[i] = (new_start, new_end);
+                *merged_range = (new_start, new_end);
                 break;
             }
         }
@@ -101,4 +132,68 @@ fn do_ranges_overlap(range_1: &(u64, u64), range_2: &(u64, u64)) -> bool {
     // Ranges overlap if range_1 doesn't end before range_2 starts
     // AND range_2 doesn't end before range_1 starts
     range_1.1 >= range_2.0 && range_2.1 >= range_1.0
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Rng;
+
+    const EXAMPLE: &str = "\
+3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32
+";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 3);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 14);
+    }
+
+    #[test]
+    fn part_2_without_ranges() {
+        assert_eq!(solve_02("\n1\n2\n").unwrap(), 0);
+    }
+
+    #[test]
+    fn merge_ranges_preserves_coverage() {
+        let mut rng = Rng::new(5);
+
+        for _ in 0..500 {
+            let ranges: Vec<(u64, u64)> = (0..rng.range(1..=8))
+                .map(|_| {
+                    let start = rng.range(0..=50);
+                    (start, start + rng.range(0..=15))
+                })
+                .collect();
+
+            let merged = merge_ranges(ranges.clone());
+
+            // Every id is covered by the merged ranges iff it was covered before
+            for id in 0..=70 {
+                let covered = ranges.iter().any(|&(start, end)| (start..=end).contains(&id));
+                let merged_covered = merged.iter().filter(|&&(start, end)| (start..=end).contains(&id)).count();
+                assert_eq!(merged_covered, covered as usize, "id {id} in {ranges:?} -> {merged:?}");
+            }
+
+            // No two merged ranges overlap
+            for (i, a) in merged.iter().enumerate() {
+                for b in &merged[i + 1..] {
+                    assert!(!do_ranges_overlap(a, b), "{a:?} overlaps {b:?}");
+                }
+            }
+        }
+    }
+}
@@ -1,16 +1,8 @@
 use std::collections::HashMap;
 
-use crate::utils::read_file;
-
-const TASK_VERSION: u8 = 2;
-
-pub fn grand_total(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => grand_total_01(path),
-        2 => grand_total_02(path),
-        _ => Err("Invalid task version!".into()),
-    }
-}
+use crate::error::Error;
+use crate::solution::Solution;
+use crate::utils::{ read_file, Grid };
 
 #[derive(PartialEq)]
 enum Operation {
@@ -42,18 +34,52 @@ impl Column {
     }
 }
 
-pub fn grand_total_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        6
+    }
+
+    fn title(&self) -> &'static str {
+        "Trash Compactor"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        parse_worksheet(input).map(drop)
+    }
+}
+
+/// Solve part one for the puzzle input file at `path`
+pub fn grand_total_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
 
+/// Solve part two for the puzzle input file at `path`
+pub fn grand_total_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
     let mut map = HashMap::new();
-    for line in input.lines() {
+    for (line_idx, line) in input.lines().enumerate() {
+        let line_no = line_idx + 1;
         let nums_or_ops = line.split(" ");
 
         let mut idx = 0;
         for num_or_op in nums_or_ops {
             if num_or_op == "+" || num_or_op == "*" {
                 let column: &mut Column = map.get_mut(&idx)
-                    .ok_or_else(|| format!("No column found for index {}", idx))?;
+                    .ok_or_else(|| Error::parse_at(line_no, line, num_or_op, format!("No column found for index {}", idx)))?;
                 
                 column.operation = if num_or_op == "+" {
                     Operation::Addition
@@ -63,12 +89,12 @@ pub fn grand_total_01(path: &str) -> Result<u64, String> {
 
                 idx += 1;
             }
-            else if num_or_op == "" {
+            else if num_or_op.is_empty() {
                 continue;
             }
             else {
                 let value = num_or_op.parse::<u64>()
-                    .map_err(|err| format!("Wrong input: {err}!"))?;
+                    .map_err(|err| Error::parse_at(line_no, line, num_or_op, format!("Wrong input: {err}!")))?;
 
                 map.entry(idx).or_insert_with(Column::new).nums.push(value);
 
@@ -88,46 +114,26 @@ pub fn grand_total_01(path: &str) -> Result<u64, String> {
     Ok(grand_total)
 }
 
-pub fn grand_total_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    // Every line has the same width, so the worksheet can be read column by column
+    let grid = parse_worksheet(input)?;
     let lines: Vec<&str> = input.lines().collect();
-    if lines.is_empty() {
-        return Err("Empty input".into());
-    }
-
-    let height = lines.len();
-    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
-
-    // Normalize all lines to equal width
-    let grid: Vec<Vec<char>> = lines
-        .into_iter()
-        .map(|l| {
-            let mut row: Vec<char> = l.chars().collect();
-            row.resize(width, ' ');
-            row
-        })
-        .collect();
+    let columns = grid.transpose();
+    let operator_row = grid.height() - 1;
 
     // Group columns into problems
     // A problem consists of consecutive columns that aren't all spaces
-    // The operator is in the last row of the rightmost column
     let mut problems: Vec<Vec<usize>> = Vec::new();
     let mut current_problem: Vec<usize> = Vec::new();
 
-    for col in 0..width {
-        // Check if this column has any content (non-space in any row)
-        let has_content = (0..height).any(|row| grid[row][col] != ' ');
-
-        if has_content {
+    for (col, column) in columns.rows().enumerate() {
+        if column.iter().any(|&cell| cell != b' ') {
             // Add to current problem
             current_problem.push(col);
-        } else {
-            // Empty column - end current problem if it exists
-            if !current_problem.is_empty() {
-                problems.push(current_problem.clone());
-                current_problem.clear();
-            }
+        } else if !current_problem.is_empty() {
+            // Empty column - end current problem
+            problems.push(std::mem::take(&mut current_problem));
         }
     }
 
@@ -141,8 +147,8 @@ pub fn grand_total_02(path: &str) -> Result<u64, String> {
     // Process each problem
     for problem_cols in problems {
         // The operator is in the last row of the LEFTMOST column of the problem
-        let leftmost_col = *problem_cols.first().unwrap();
-        let op = grid[height - 1][leftmost_col];
+        let leftmost_col = problem_cols[0];
+        let op = grid[(operator_row, leftmost_col)] as char;
 
         // Each column forms one number (reading top to bottom)
         // We process columns right-to-left
@@ -150,11 +156,12 @@ pub fn grand_total_02(path: &str) -> Result<u64, String> {
 
         for &col in problem_cols.iter().rev() {
             let mut num = 0u64;
-            for row in 0..height - 1 {
-                let c = grid[row][col];
-                if c.is_ascii_digit() {
-                    let digit = c.to_digit(10).unwrap() as u64;
-                    num = num * 10 + digit;
+            for (row_idx, &cell) in columns.row(col)[..operator_row].iter().enumerate() {
+                if cell.is_ascii_digit() {
+                    let digit = (cell - b'0') as u64;
+                    num = num.checked_mul(10)
+                        .and_then(|num| num.checked_add(digit))
+                        .ok_or_else(|| Error::parse(row_idx + 1, lines[row_idx], col + 1, "Number does not fit into u64"))?;
                 }
             }
             if num > 0 {
@@ -162,18 +169,51 @@ pub fn grand_total_02(path: &str) -> Result<u64, String> {
             }
         }
 
+        let operator_line = lines[operator_row];
         if numbers.is_empty() {
-            return Err(format!("Problem has operator '{op}' but no numbers."));
+            return Err(Error::parse(operator_row + 1, operator_line, leftmost_col + 1, format!("Problem has operator '{op}' but no numbers.")));
         }
 
         let result: u64 = match op {
             '+' => numbers.iter().sum(),
             '*' => numbers.iter().product(),
-            _ => return Err(format!("Invalid operator '{op}'")),
+            _ => return Err(Error::parse(operator_row + 1, operator_line, leftmost_col + 1, format!("Invalid operator '{op}'"))),
         };
 
         grand_total += result;
     }
 
     Ok(grand_total)
-}
\ No newline at end of file
+}
+
+/// The worksheet with short lines padded with spaces to equal width
+fn parse_worksheet(input: &str) -> Result<Grid<u8>, Error> {
+    if input.lines().next().is_none() {
+        return Err(Error::InvalidInput("Empty input".into()));
+    }
+
+    Grid::parse_padded(input, b' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trailing spaces are significant for part two
+    const EXAMPLE: &str = "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   +  \n";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 4277556);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 3263827);
+    }
+
+    #[test]
+    fn part_2_invalid_operator() {
+        assert!(matches!(solve_02("12\n34\n/ \n"), Err(Error::Parse(_))));
+    }
+}
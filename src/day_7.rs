@@ -1,175 +1,184 @@
 use std::collections::{ HashSet, HashMap };
 
-use crate::utils::read_file;
+use crate::error::Error;
+use crate::solution::Solution;
+use crate::utils::{ read_file, Grid };
 
-const TASK_VERSION: u8 = 2;
+pub struct Puzzle;
 
-pub fn tachyon(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => tachyon_01(path),
-        2 => tachyon_02(path),
-        _ => Err("Invalid task version!".into()),
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        7
     }
-}
 
-pub fn tachyon_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+    fn title(&self) -> &'static str {
+        "Laboratories"
+    }
 
-    let grid: Vec<String> = input
-        .lines()
-        .map(|line| line.to_string())
-        .collect();
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
 
-    if grid.is_empty() {
-        return Err("Input grid is empty".into());
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
     }
 
-    let h = grid.len();
-    let w = grid[0].len();
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        Grid::parse(input).map(drop)
+    }
+}
 
-    // --- Find S ---
-    let mut start = None;
-    for (y, row) in grid.iter().enumerate() {
-        if let Some(x) = row.find('S') {
-            start = Some((x as isize, y as isize));
-            break;
-        }
+/// Solve part one for the puzzle input file at `path`
+pub fn tachyon_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part two for the puzzle input file at `path`
+pub fn tachyon_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    let grid = Grid::parse(input)?;
+
+    if grid.is_empty() {
+        return Err(Error::InvalidInput("Input grid is empty".into()));
     }
 
-    let (sx, sy) = match start {
-        Some(pos) => pos,
-        None => return Err("Could not find 'S' in the input".into()),
-    };
+    let start = grid.find(&b'S')
+        .ok_or_else(|| Error::InvalidInput("Could not find 'S' in the input".into()))?;
 
-    let mut visited: HashSet<(isize, isize)> = HashSet::new();
+    let mut visited: HashSet<(usize, usize)> = HashSet::new();
     let mut splits: u64 = 0;
 
     // Stack = beam origins
-    let mut stack = vec![(sx, sy)];
-
-    while let Some((x, mut y)) = stack.pop() {
-        // Beam travels downward
-        loop {
-            y += 1;
-            if y < 0 || y >= h as isize {
-                break; // out of grid
-            }
+    let mut stack = vec![start];
 
-            let cell = grid[y as usize].as_bytes()[x as usize] as char;
+    while let Some((mut row, col)) = stack.pop() {
+        // Beam travels downward until it leaves the grid
+        while let Some(&cell) = grid.get(row + 1, col) {
+            row += 1;
 
-            match cell {
-                '^' => {
-                    // Beam stops here
-                    if visited.insert((x, y)) {
-                        splits += 1;
+            if cell == b'^' {
+                // Beam stops here
+                if visited.insert((row, col)) {
+                    splits += 1;
 
-                        // spawn left and right beams
-                        let left = x - 1;
-                        let right = x + 1;
-
-                        if left >= 0 && left < w as isize {
-                            stack.push((left, y));
-                        }
-                        if right >= 0 && right < w as isize {
-                            stack.push((right, y));
-                        }
+                    // spawn left and right beams
+                    if col > 0 {
+                        stack.push((row, col - 1));
+                    }
+                    if col + 1 < grid.width() {
+                        stack.push((row, col + 1));
                     }
-                    break; // splitter stops this beam
-                }
-                '.' | 'S' => {
-                    // Keep falling
-                }
-                _ => {
-                    // Any unknown char = treat as empty
                 }
+                break; // splitter stops this beam
             }
+
+            // Anything else, '.', 'S' or unknown, is treated as empty
         }
     }
 
     Ok(splits)
 }
 
-pub fn tachyon_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-
-    // Parse grid
-    let grid: Vec<Vec<char>> = input
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect();
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    let grid = Grid::parse(input)?;
 
     if grid.is_empty() {
-        return Err("Grid is empty".into());
+        return Err(Error::InvalidInput("Grid is empty".into()));
     }
 
-    let h = grid.len() as isize;
-    let w = grid[0].len() as isize;
+    let start = grid.find(&b'S')
+        .ok_or_else(|| Error::InvalidInput("No 'S' found in input".into()))?;
 
-    // --- Find S ---
-    let mut start = None;
-    for (y, row) in grid.iter().enumerate() {
-        if let Some(x) = row.iter().position(|&c| c == 'S') {
-            start = Some((x as isize, y as isize));
-            break;
-        }
-    }
-    let (sx, sy) = match start {
-        Some(pos) => pos,
-        None => return Err("No 'S' found in input".into()),
-    };
-
-    // Memoization table: (x,y) -> number of beam completions
-    let mut memo: HashMap<(isize, isize), u64> = HashMap::new();
+    // Memoization table: (row, col) -> number of beam completions
+    let mut memo: HashMap<(usize, usize), u64> = HashMap::new();
 
-    // Recursive function that counts all completions from (x,y)
+    // Recursive function that counts all completions from (row, col)
     fn ways_from(
-        x: isize,
-        y: isize,
-        grid: &Vec<Vec<char>>,
-        w: isize,
-        h: isize,
-        memo: &mut HashMap<(isize, isize), u64>,
+        row: usize,
+        col: usize,
+        grid: &Grid<u8>,
+        memo: &mut HashMap<(usize, usize), u64>,
     ) -> u64 {
-        if let Some(&cached) = memo.get(&(x, y)) {
+        if let Some(&cached) = memo.get(&(row, col)) {
             return cached;
         }
 
-        let mut cy = y;
+        let mut current = row;
         // Drop downward until leaving grid or hitting a splitter
-        loop {
-            cy += 1;
-            if cy >= h {
-                // Out of grid → this path contributes exactly 1 completion
-                memo.insert((x, y), 1);
-                return 1;
-            }
-
-            let cell = grid[cy as usize][x as usize];
+        let total = loop {
+            current += 1;
 
-            if cell == '^' {
+            match grid.get(current, col) {
+                // Out of grid → this path contributes exactly 1 completion
+                None => break 1,
                 // Hit a splitter: the beam can go left OR right
-                let mut total = 0;
-
-                let left = x - 1;
-                if left >= 0 {
-                    total += ways_from(left, cy, grid, w, h, memo);
-                }
+                Some(b'^') => {
+                    let mut total = 0;
 
-                let right = x + 1;
-                if right < w {
-                    total += ways_from(right, cy, grid, w, h, memo);
-                }
+                    if col > 0 {
+                        total += ways_from(current, col - 1, grid, memo);
+                    }
+                    if col + 1 < grid.width() {
+                        total += ways_from(current, col + 1, grid, memo);
+                    }
 
-                memo.insert((x, y), total);
-                return total;
+                    break total;
+                },
+                // Otherwise '.', 'S', etc. → keep falling downward
+                Some(_) => {},
             }
+        };
 
-            // Otherwise '.', 'S', etc. → keep falling downward
-        }
+        memo.insert((row, col), total);
+        total
     }
 
     // Calculate number of paths from the starting beam position
-    let total = ways_from(sx, sy, &grid, w, h, &mut memo);
+    let total = ways_from(start.0, start.1, &grid, &mut memo);
 
     Ok(total)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+.......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............
+";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 21);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 40);
+    }
+
+    #[test]
+    fn missing_start() {
+        assert!(matches!(solve_01("...\n.^.\n"), Err(Error::InvalidInput(_))));
+    }
+}
@@ -1,17 +1,22 @@
 use std::collections::BinaryHeap;
 
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 
-const TASK_VERSION: u8 = 2;
+/// Number of closest pairs connected in part one
+const CONNECTIONS: usize = 1000;
 
-#[derive(Clone, Copy)]
-struct Coor {
+/// Position of a junction box in 3D space
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coor {
     pub x: i64,
     pub y: i64,
     pub z: i64,
 }
 
 impl Coor {
+    /// Squared euclidean distance, enough for comparing distances without floats
     pub fn calc_dist2(&self, other: &Coor) -> i64 {
         let dx = self.x - other.x;
         let dy = self.y - other.y;
@@ -20,28 +25,62 @@ impl Coor {
     }
 }
 
-pub fn junction_boxes(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => junction_boxes_01(path),
-        2 => junction_boxes_02(path),
-        _ => Err("Invalid task version!".into()),
+pub struct Puzzle;
+
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        8
+    }
+
+    fn title(&self) -> &'static str {
+        "Playground"
+    }
+
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        input.lines()
+            .enumerate()
+            .try_for_each(|(line_idx, line)| parse_coordinate(line_idx + 1, line).map(drop))
     }
 }
 
-pub fn junction_boxes_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+/// Solve part one for the puzzle input file at `path`
+pub fn junction_boxes_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part two for the puzzle input file at `path`
+pub fn junction_boxes_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    largest_circuits(input, CONNECTIONS)
+}
 
+/// Connect the `connections` closest pairs of junction boxes and
+/// multiply the sizes of the three largest circuits
+pub fn largest_circuits(input: &str, connections: usize) -> Result<u64, Error> {
     let boxes: Vec<Coor> = input
         .lines()
-        .map(parse_coordinate)
+        .enumerate()
+        .map(|(line_idx, line)| parse_coordinate(line_idx + 1, line))
         .collect::<Result<_, _>>()?;
 
     let count = boxes.len();
     if count < 3 {
-        return Err("Need at least 3 junction boxes".into());
+        return Err(Error::InvalidInput("Need at least 3 junction boxes".into()));
     }
 
-    // Keep the 1000 shortest edges using a max-heap of (distance, i, j).
+    // Keep the `connections` shortest edges using a max-heap of (distance, i, j).
     // The heap stores the largest distance at the top, so when we see a
     // new distance smaller than the top we replace it.
     let mut closest_pairs: BinaryHeap<(i64, usize, usize)> = BinaryHeap::new();
@@ -49,9 +88,9 @@ pub fn junction_boxes_01(path: &str) -> Result<u64, String> {
     for i in 0..count {
         for j in (i + 1)..count {
             let distance = boxes[i].calc_dist2(&boxes[j]);
-            if closest_pairs.len() < 1000 {
+            if closest_pairs.len() < connections {
                 closest_pairs.push((distance, i, j));
-            } else if distance < closest_pairs.peek().unwrap().0 {
+            } else if closest_pairs.peek().is_some_and(|largest| distance < largest.0) {
                 // current distance is smaller than the largest in the heap;
                 // replace the largest with this smaller distance
                 closest_pairs.pop();
@@ -70,37 +109,36 @@ pub fn junction_boxes_01(path: &str) -> Result<u64, String> {
 
     // Collect component sizes
     let mut component_sizes = Vec::new();
-    for i in 0..count {
+    for (i, &size) in sizes.iter().enumerate() {
         if find(&mut parents, i) == i {
-            component_sizes.push(sizes[i]);
+            component_sizes.push(size);
         }
     }
 
     component_sizes.sort_unstable_by(|a, b| b.cmp(a));
 
     if component_sizes.len() < 3 {
-        return Err("Not enough circuits to multiply top 3".into());
+        return Err(Error::Unsatisfiable("Not enough circuits to multiply top 3".into()));
     }
 
     Ok(component_sizes[0] * component_sizes[1] * component_sizes[2])
 }
 
-pub fn junction_boxes_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
     let boxes: Vec<Coor> = input
         .lines()
-        .map(parse_coordinate)
+        .enumerate()
+        .map(|(line_idx, line)| parse_coordinate(line_idx + 1, line))
         .collect::<Result<_, _>>()?;
 
     let count = boxes.len();
     if count < 2 {
-        return Err("Need at least 2 junction boxes".into());
+        return Err(Error::InvalidInput("Need at least 2 junction boxes".into()));
     }
 
     // Build all pair distances
-    let mut edges: Vec<(i64, usize, usize)> = Vec::new();
-    edges.reserve(count * (count - 1) / 2);
+    let mut edges: Vec<(i64, usize, usize)> = Vec::with_capacity(count * (count - 1) / 2);
 
     for i in 0..count {
         for j in (i + 1)..count {
@@ -136,34 +174,43 @@ pub fn junction_boxes_02(path: &str) -> Result<u64, String> {
         }
     }
 
-    let (a, b) = last_pair.ok_or("Graph was already connected")?;
+    let (a, b) = last_pair.ok_or_else(|| Error::Unsatisfiable("Graph was already connected".into()))?;
     let result = (boxes[a].x as u64) * (boxes[b].x as u64);
 
     Ok(result)
 }
 
-fn parse_coordinate(line: &str) -> Result<Coor, String> {
+/// Parse a single `x,y,z` line, `line_no` is only used for error reporting
+pub fn parse_coordinate(line_no: usize, line: &str) -> Result<Coor, Error> {
     let parts: Vec<&str> = line.split(',').collect();
 
     if parts.len() != 3 {
-        return Err(format!("Cannot parse coordinates: {line}"));
+        return Err(Error::parse_at(line_no, line, line, "Cannot parse coordinates, expected x,y,z"));
     }
 
+    let parse_axis = |part: &str| {
+        part.parse::<i64>().map_err(|e| Error::parse_at(line_no, line, part, format!("{e}")))
+    };
+
     Ok(Coor {
-        x: parts[0].parse::<i64>().map_err(|e| format!("{e}"))?,
-        y: parts[1].parse::<i64>().map_err(|e| format!("{e}"))?,
-        z: parts[2].parse::<i64>().map_err(|e| format!("{e}"))?,
+        x: parse_axis(parts[0])?,
+        y: parse_axis(parts[1])?,
+        z: parse_axis(parts[2])?,
     })
 }
 
-fn find(parents: &mut [usize], x: usize) -> usize {
+/// Union–find lookup with path compression.
+/// `parents` starts as the identity mapping `(0..n).collect()`.
+pub fn find(parents: &mut [usize], x: usize) -> usize {
     if parents[x] != x {
         parents[x] = find(parents, parents[x]);
     }
     parents[x]
 }
 
-fn union(parents: &mut [usize], sizes: &mut [u64], a: usize, b: usize) {
+/// Union by size of the components containing `a` and `b`.
+/// `sizes` starts as all ones and is only meaningful for root indices.
+pub fn union(parents: &mut [usize], sizes: &mut [u64], a: usize, b: usize) {
     let mut root_a = find(parents, a);
     let mut root_b = find(parents, b);
 
@@ -178,3 +225,79 @@ fn union(parents: &mut [usize], sizes: &mut [u64], a: usize, b: usize) {
     parents[root_b] = root_a;
     sizes[root_a] += sizes[root_b];
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::Rng;
+
+    const EXAMPLE: &str = "\
+162,817,812
+57,618,57
+906,360,560
+592,479,940
+352,342,300
+466,668,158
+542,29,236
+431,825,988
+739,650,466
+52,470,668
+216,146,977
+819,987,18
+117,168,530
+805,96,715
+346,949,466
+970,615,88
+941,993,340
+862,61,35
+984,92,344
+425,690,689
+";
+
+    #[test]
+    fn part_1_example() {
+        // The example connects only the ten closest pairs
+        assert_eq!(largest_circuits(EXAMPLE, 10).unwrap(), 40);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 25272);
+    }
+
+    #[test]
+    fn union_find_matches_naive_labelling() {
+        let mut rng = Rng::new(8);
+
+        for _ in 0..200 {
+            let count = rng.range(1..=30) as usize;
+            let mut parents: Vec<usize> = (0..count).collect();
+            let mut sizes: Vec<u64> = vec![1; count];
+            // Naive reference: every element carries the label of its component
+            let mut labels: Vec<usize> = (0..count).collect();
+
+            for _ in 0..rng.range(0..=40) {
+                let a = rng.range(0..=count as u64 - 1) as usize;
+                let b = rng.range(0..=count as u64 - 1) as usize;
+
+                union(&mut parents, &mut sizes, a, b);
+
+                let (from, to) = (labels[b], labels[a]);
+                for label in labels.iter_mut().filter(|label| **label == from) {
+                    *label = to;
+                }
+            }
+
+            for a in 0..count {
+                for b in 0..count {
+                    let same_root = find(&mut parents, a) == find(&mut parents, b);
+                    assert_eq!(same_root, labels[a] == labels[b]);
+                }
+
+                let root = find(&mut parents, a);
+                let component_size = labels.iter().filter(|&&label| label == labels[a]).count();
+                assert_eq!(sizes[root], component_size as u64);
+            }
+        }
+    }
+}
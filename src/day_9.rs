@@ -1,3 +1,5 @@
+use crate::error::Error;
+use crate::solution::Solution;
 use crate::utils::read_file;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -6,27 +8,43 @@ struct Pt {
     y: i64,
 }
 
-const TASK_VERSION: u8 = 2;
+pub struct Puzzle;
 
-pub fn rectangle(path: &str) -> Result<u64, String> {
-    match TASK_VERSION {
-        1 => rectangle_01(path),
-        2 => rectangle_02(path),
-        _ => Err("Invalid task version!".into()),
+impl Solution for Puzzle {
+    fn day(&self) -> u8 {
+        9
     }
-}
 
-pub fn rectangle_01(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
+    fn title(&self) -> &'static str {
+        "Movie Theater"
+    }
 
-    let mut red: Vec<Pt> = vec![];
-    for line in input.lines().filter(|l| !l.trim().is_empty()) {
-        let (sx, sy) = line.split_once(',').ok_or(format!("Invalid line: {}", line))?;
-        red.push(Pt {
-            x: sx.parse().map_err(|_| "bad x")?,
-            y: sy.parse().map_err(|_| "bad y")?
-        });
+    fn part1(&self, input: &str) -> Result<u64, Error> {
+        solve_01(input)
+    }
+
+    fn part2(&self, input: &str) -> Result<u64, Error> {
+        solve_02(input)
+    }
+
+    fn parse(&self, input: &str) -> Result<(), Error> {
+        parse_points(input).map(drop)
     }
+}
+
+/// Solve part one for the puzzle input file at `path`
+pub fn rectangle_01(path: &str) -> Result<u64, Error> {
+    solve_01(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part two for the puzzle input file at `path`
+pub fn rectangle_02(path: &str) -> Result<u64, Error> {
+    solve_02(&read_file(path)?).map_err(|err| err.in_file(path))
+}
+
+/// Solve part one for the puzzle input text
+pub fn solve_01(input: &str) -> Result<u64, Error> {
+    let red = parse_points(input)?;
 
     let mut best = 0;
     for i in 0..red.len() {
@@ -41,17 +59,9 @@ pub fn rectangle_01(path: &str) -> Result<u64, String> {
     Ok(best)
 }
 
-pub fn rectangle_02(path: &str) -> Result<u64, String> {
-    let input = read_file(path);
-
-    let mut red: Vec<Pt> = vec![];
-    for line in input.lines().filter(|l| !l.trim().is_empty()) {
-        let (sx, sy) = line.split_once(',').ok_or(format!("Invalid line: {}", line))?;
-        red.push(Pt {
-            x: sx.parse().map_err(|_| "bad x")?,
-            y: sy.parse().map_err(|_| "bad y")?
-        });
-    }
+/// Solve part two for the puzzle input text
+pub fn solve_02(input: &str) -> Result<u64, Error> {
+    let red = parse_points(input)?;
 
     let mut best = 0;
 
@@ -63,7 +73,7 @@ pub fn rectangle_02(path: &str) -> Result<u64, String> {
             pairs.push((i, j, area));
         }
     }
-    pairs.sort_unstable_by(|a, b| b.2.cmp(&a.2));
+    pairs.sort_unstable_by_key(|pair| std::cmp::Reverse(pair.2));
 
     for (i, j, area) in pairs {
         if area <= best {
@@ -82,10 +92,26 @@ pub fn rectangle_02(path: &str) -> Result<u64, String> {
     Ok(best)
 }
 
+/// Parse one red tile `x,y` per line, skipping blank lines
+fn parse_points(input: &str) -> Result<Vec<Pt>, Error> {
+    let mut red: Vec<Pt> = vec![];
+    for (line_idx, line) in input.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+        let line_no = line_idx + 1;
+        let (sx, sy) = line.split_once(',')
+            .ok_or_else(|| Error::parse_at(line_no, line, line, "Invalid line, expected x,y"))?;
+        red.push(Pt {
+            x: sx.parse().map_err(|err| Error::parse_at(line_no, line, sx, format!("bad x: {err}")))?,
+            y: sy.parse().map_err(|err| Error::parse_at(line_no, line, sy, format!("bad y: {err}")))?
+        });
+    }
+
+    Ok(red)
+}
+
 /// Compute inclusive tile area
 fn rect_area(a: Pt, b: Pt) -> u64 {
-    let width = (a.x - b.x).abs() as u64 + 1;
-    let height = (a.y - b.y).abs() as u64 + 1;
+    let width = a.x.abs_diff(b.x) + 1;
+    let height = a.y.abs_diff(b.y) + 1;
     width * height
 }
 
@@ -210,3 +236,35 @@ fn is_rect_valid(polygon: &[Pt], corner1: Pt, corner2: Pt) -> bool {
 
     true
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+7,1
+11,1
+11,7
+9,7
+9,5
+2,5
+2,3
+7,3
+";
+
+    #[test]
+    fn part_1_example() {
+        assert_eq!(solve_01(EXAMPLE).unwrap(), 50);
+    }
+
+    #[test]
+    fn part_2_example() {
+        assert_eq!(solve_02(EXAMPLE).unwrap(), 24);
+    }
+
+    #[test]
+    fn rect_area_is_inclusive() {
+        assert_eq!(rect_area(Pt { x: 2, y: 5 }, Pt { x: 11, y: 1 }), 50);
+        assert_eq!(rect_area(Pt { x: 3, y: 3 }, Pt { x: 3, y: 3 }), 1);
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong while reading or solving a puzzle
+#[derive(Debug)]
+pub enum Error {
+    /// The puzzle input could not be read
+    Io { path: String, source: io::Error },
+    /// The puzzle input is malformed
+    Parse(ParseError),
+    /// The puzzle input is well-formed but does not describe a valid puzzle,
+    /// e.g. an empty grid or too few junction boxes
+    InvalidInput(String),
+    /// The puzzle has no solution for the given input, e.g. an inconsistent system of equations
+    Unsatisfiable(String),
+    /// The input is beyond what the solver is able to handle, e.g. too many buttons
+    SolverLimit(String),
+    /// A request to the puzzle server failed or was refused
+    Http { url: String, message: String },
+}
+
+/// Location and description of malformed input.
+/// Lines and columns are 1-based, columns count characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Input file, if the input was read from one
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    /// Number of characters underlined in the excerpt, at least 1
+    pub width: usize,
+    /// The offending line of input
+    pub excerpt: String,
+    pub message: String,
+}
+
+impl Error {
+    /// Parse error pointing at a single character of `line`
+    pub fn parse(line_no: usize, line: &str, column: usize, message: impl Into<String>) -> Self {
+        Error::Parse(ParseError {
+            file: None,
+            line: line_no,
+            column,
+            width: 1,
+            excerpt: line.to_string(),
+            message: message.into(),
+        })
+    }
+
+    /// Parse error pointing at `token`, which must be a subslice of `line`
+    pub fn parse_at(line_no: usize, line: &str, token: &str, message: impl Into<String>) -> Self {
+        Error::Parse(ParseError {
+            file: None,
+            line: line_no,
+            column: column_of(line, token),
+            width: token.chars().count().max(1),
+            excerpt: line.to_string(),
+            message: message.into(),
+        })
+    }
+
+    /// Attach the input file to a parse error
+    pub fn in_file(self, path: &str) -> Self {
+        match self {
+            Error::Parse(mut err) => {
+                err.file = Some(path.to_string());
+                Error::Parse(err)
+            },
+            other => other,
+        }
+    }
+
+    /// Move a parse error down by `lines`, for errors from parsing an excerpt of the input
+    pub fn offset_lines(self, lines: usize) -> Self {
+        match self {
+            Error::Parse(mut err) => {
+                err.line += lines;
+                Error::Parse(err)
+            },
+            other => other,
+        }
+    }
+}
+
+/// Column (1-based, in characters) where `token` starts within `line`.
+/// Falls back to the first column if `token` is not a subslice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    line.get(..offset).map_or(1, |prefix| prefix.chars().count() + 1)
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io { path, source } => write!(f, "Unable to read {path}: {source}"),
+            Error::Parse(err) => write!(f, "Parse error: {err}"),
+            Error::InvalidInput(message) => write!(f, "Invalid input: {message}"),
+            Error::Unsatisfiable(message) => write!(f, "No solution: {message}"),
+            Error::SolverLimit(message) => write!(f, "Solver limit exceeded: {message}"),
+            Error::Http { url, message } => write!(f, "Request to {url} failed: {message}"),
+        }
+    }
+}
+
+/// Renders the error with a caret-style excerpt:
+///
+/// ```text
+/// bad y: invalid digit found in string
+///  --> data/day_9.txt:3:4
+///   |
+/// 3 | 12,ab
+///   |    ^^
+/// ```
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.file.as_deref().unwrap_or("<input>");
+        let gutter = " ".repeat(self.line.to_string().len());
+
+        writeln!(f, "{}", self.message)?;
+        writeln!(f, "{gutter}--> {file}:{}:{}", self.line, self.column)?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{} | {}", self.line, self.excerpt)?;
+        write!(f, "{gutter} | {}{}", " ".repeat(self.column.saturating_sub(1)), "^".repeat(self.width))
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_of_subslice() {
+        let line = "12,ab";
+        assert_eq!(column_of(line, &line[3..]), 4);
+        assert_eq!(column_of(line, line), 1);
+        assert_eq!(column_of(line, &String::from("ab")), 1);
+    }
+
+    #[test]
+    fn renders_caret_excerpt() {
+        let line = "12,ab";
+        let err = Error::parse_at(3, line, &line[3..], "bad y").in_file("data/day_9.txt");
+
+        assert_eq!(err.to_string(), "\
+Parse error: bad y
+ --> data/day_9.txt:3:4
+  |
+3 | 12,ab
+  |    ^^");
+    }
+}
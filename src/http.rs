@@ -0,0 +1,172 @@
+use std::io::{ Read, Write };
+use std::net::TcpStream;
+use std::process::{ Command, Stdio };
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// Identifies the runner to the puzzle server, as it asks automated tools to do
+pub const USER_AGENT: &str = "aoc2025 (github.com/Cemonix/AoC2025)";
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Status and body of an HTTP response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// `GET url`, sending `headers` as `(name, value)` pairs
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<Response, Error> {
+    request("GET", url, headers, None)
+}
+
+/// `POST url` with a form-encoded `body`
+pub fn post_form(url: &str, headers: &[(&str, &str)], body: &str) -> Result<Response, Error> {
+    let mut headers = headers.to_vec();
+    headers.push(("Content-Type", "application/x-www-form-urlencoded"));
+    request("POST", url, &headers, Some(body))
+}
+
+/// Plain `http://` is spoken directly over TCP, `https://` goes through the system `curl`
+fn request(method: &str, url: &str, headers: &[(&str, &str)], body: Option<&str>) -> Result<Response, Error> {
+    if let Some(rest) = url.strip_prefix("http://") {
+        request_plain(method, url, rest, headers, body)
+    } else if url.starts_with("https://") {
+        request_curl(method, url, headers, body)
+    } else {
+        Err(Error::Http { url: url.to_string(), message: "Only http:// and https:// URLs are supported".into() })
+    }
+}
+
+fn request_plain(
+    method: &str,
+    url: &str,
+    rest: &str,
+    headers: &[(&str, &str)],
+    body: Option<&str>,
+) -> Result<Response, Error> {
+    let http_err = |message: String| Error::Http { url: url.to_string(), message };
+
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => (&rest[..idx], &rest[idx..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') { authority.to_string() } else { format!("{authority}:80") };
+
+    let mut stream = TcpStream::connect(&address).map_err(|err| http_err(err.to_string()))?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(|err| http_err(err.to_string()))?;
+
+    let mut request = format!("{method} {path} HTTP/1.1\r\nHost: {authority}\r\nUser-Agent: {USER_AGENT}\r\nConnection: close\r\n");
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    let body = body.unwrap_or("");
+    if method != "GET" {
+        request.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+
+    stream.write_all(request.as_bytes()).map_err(|err| http_err(err.to_string()))?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).map_err(|err| http_err(err.to_string()))?;
+
+    parse_response(&raw).map_err(http_err)
+}
+
+/// Split a raw HTTP/1.1 response into status and body, decoding a chunked body
+fn parse_response(raw: &[u8]) -> Result<Response, String> {
+    let raw = String::from_utf8_lossy(raw);
+    let (head, body) = raw.split_once("\r\n\r\n").ok_or("Malformed response, no end of headers")?;
+
+    let mut lines = head.lines();
+    let status = lines.next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())
+        .ok_or("Malformed response status line")?;
+
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding") && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+
+    let body = if chunked { decode_chunked(body)? } else { body.to_string() };
+    Ok(Response { status, body })
+}
+
+fn decode_chunked(mut body: &str) -> Result<String, String> {
+    let mut decoded = String::new();
+
+    loop {
+        let (size, rest) = body.split_once("\r\n").ok_or("Malformed chunk header")?;
+        let size = usize::from_str_radix(size.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|err| format!("Malformed chunk size: {err}"))?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+
+        let chunk = rest.get(..size).ok_or("Truncated chunk")?;
+        decoded.push_str(chunk);
+        body = rest.get(size..).and_then(|rest| rest.strip_prefix("\r\n")).ok_or("Malformed chunk end")?;
+    }
+}
+
+/// Headers are handed to curl on stdin so values like the session cookie never show up in the process list
+fn request_curl(method: &str, url: &str, headers: &[(&str, &str)], body: Option<&str>) -> Result<Response, Error> {
+    let http_err = |message: String| Error::Http { url: url.to_string(), message };
+
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--max-time", &TIMEOUT.as_secs().to_string()])
+        .args(["--request", method, "--user-agent", USER_AGENT, "--header", "@-"])
+        .args(["--write-out", "\n%{http_code}"]);
+    if let Some(body) = body {
+        command.args(["--data-raw", body]);
+    }
+    command.arg(url).stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    let mut child = command.spawn().map_err(|err| http_err(format!("Unable to run curl: {err}")))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let header_lines: String = headers.iter().map(|(name, value)| format!("{name}: {value}\n")).collect();
+        stdin.write_all(header_lines.as_bytes()).map_err(|err| http_err(err.to_string()))?;
+    }
+
+    let output = child.wait_with_output().map_err(|err| http_err(err.to_string()))?;
+    if !output.status.success() {
+        return Err(http_err(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').ok_or_else(|| http_err("Missing status from curl".into()))?;
+    let status = status.trim().parse::<u16>().map_err(|err| http_err(format!("Invalid status from curl: {err}")))?;
+
+    Ok(Response { status, body: body.to_string() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_responses() {
+        assert_eq!(
+            parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n1\n2\n3\n"),
+            Ok(Response { status: 200, body: "1\n2\n3\n".into() })
+        );
+        assert_eq!(
+            parse_response(b"HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nmiss\r\n3\r\ning\r\n0\r\n\r\n"),
+            Ok(Response { status: 404, body: "missing".into() })
+        );
+        assert!(parse_response(b"garbage").is_err());
+    }
+}
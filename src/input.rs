@@ -0,0 +1,223 @@
+use std::io::Read;
+use std::path::Path;
+use std::{ env, fs, io };
+
+use crate::error::Error;
+use crate::http;
+use crate::utils::read_file;
+
+/// Puzzle server used unless `AOC_BASE_URL` says otherwise
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2025";
+
+/// Directory the inputs are read from and cached in
+pub const DEFAULT_DATA_DIR: &str = "data";
+
+/// Input path that stands for standard input
+pub const STDIN: &str = "-";
+
+/// Reads `day_<N>.txt` from the data directory, downloading and caching it
+/// from the puzzle server first when it is missing and a session token is configured
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputProvider {
+    data_dir: String,
+    base_url: String,
+    session: Option<String>,
+}
+
+impl InputProvider {
+    /// Provider reading from `data_dir` that never downloads
+    pub fn new(data_dir: &str) -> Self {
+        InputProvider {
+            data_dir: data_dir.to_string(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: None,
+        }
+    }
+
+    /// Provider for `data/` configured from the environment:
+    /// `AOC_BASE_URL` overrides the puzzle server, and the session token is taken from `AOC_SESSION`
+    /// or the file named by `AOC_SESSION_FILE`, defaulting to `~/.config/aoc2025/session`
+    pub fn from_env() -> Self {
+        let mut provider = InputProvider::new(DEFAULT_DATA_DIR);
+
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            provider = provider.with_base_url(&base_url);
+        }
+
+        let session_file = env::var("AOC_SESSION_FILE")
+            .ok()
+            .or_else(|| env::var("HOME").ok().map(|home| format!("{home}/.config/aoc2025/session")));
+        let session = env::var("AOC_SESSION")
+            .ok()
+            .or_else(|| session_file.and_then(|path| fs::read_to_string(path).ok()));
+
+        match session.as_deref().map(str::trim) {
+            Some(session) if !session.is_empty() => provider.with_session(session),
+            _ => provider,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_session(mut self, session: &str) -> Self {
+        self.session = Some(session.to_string());
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    /// Where the input of `day` is cached
+    pub fn path(&self, day: u8) -> String {
+        format!("{}/day_{day}.txt", self.data_dir)
+    }
+
+    /// Input of `day`, downloaded on first use if a session token is configured
+    pub fn read(&self, day: u8) -> Result<String, Error> {
+        let path = self.path(day);
+
+        match read_file(&path) {
+            Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound && self.session.is_some() => {
+                let input = self.download(day)?;
+                fs::create_dir_all(&self.data_dir).map_err(|source| Error::Io { path: self.data_dir.clone(), source })?;
+                fs::write(&path, &input).map_err(|source| Error::Io { path: path.clone(), source })?;
+                Ok(input)
+            },
+            result => result,
+        }
+    }
+
+    /// Fetch the input of `day` from the puzzle server without caching it
+    pub fn download(&self, day: u8) -> Result<String, Error> {
+        let url = format!("{}/day/{day}/input", self.base_url);
+        let session = self.session.as_deref().ok_or_else(|| Error::Http {
+            url: url.clone(),
+            message: "No session token, set AOC_SESSION".into(),
+        })?;
+
+        let response = http::get(&url, &[("Cookie", &format!("session={session}"))])?;
+        if !response.is_success() {
+            let reason = response.body.lines().next().unwrap_or("").trim();
+            return Err(Error::Http { url, message: format!("HTTP {}: {reason}", response.status) });
+        }
+
+        Ok(response.body)
+    }
+
+    /// True if the input of `day` is already on disk
+    pub fn is_cached(&self, day: u8) -> bool {
+        Path::new(&self.path(day)).is_file()
+    }
+}
+
+/// Read the file at `path`, or standard input if `path` is [`STDIN`]
+pub fn read_input(path: &str) -> Result<String, Error> {
+    if path != STDIN {
+        return read_file(path);
+    }
+
+    let mut input = String::new();
+    io::stdin()
+        .read_to_string(&mut input)
+        .map_err(|source| Error::Io { path: "<stdin>".into(), source })?;
+    Ok(input)
+}
+
+/// Replace every directory in `paths` by the files directly inside it, sorted by name.
+/// Other paths, including [`STDIN`], are kept as they are.
+pub fn expand_paths(paths: &[String]) -> Result<Vec<String>, Error> {
+    let mut expanded = Vec::new();
+
+    for path in paths {
+        if path == STDIN || !Path::new(path).is_dir() {
+            expanded.push(path.clone());
+            continue;
+        }
+
+        let io_err = |source| Error::Io { path: path.clone(), source };
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path).map_err(io_err)? {
+            let entry_path = entry.map_err(io_err)?.path();
+            if entry_path.is_file() {
+                files.push(entry_path.to_string_lossy().to_string());
+            }
+        }
+
+        if files.is_empty() {
+            return Err(Error::InvalidInput(format!("No input files in {path}")));
+        }
+
+        files.sort();
+        expanded.extend(files);
+    }
+
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ temp_dir, MockServer };
+
+    #[test]
+    fn downloads_and_caches_missing_input() {
+        let server = MockServer::start(vec![(200, "1\n2\n3\n")]);
+        let dir = temp_dir("input");
+        let data_dir = dir.join("data").to_string_lossy().to_string();
+        let provider = InputProvider::new(&data_dir).with_base_url(&server.url).with_session("secret");
+
+        assert!(!provider.is_cached(3));
+        assert_eq!(provider.read(3).unwrap(), "1\n2\n3\n");
+        assert!(provider.is_cached(3));
+        // Served from the cache, the server only answers once
+        assert_eq!(provider.read(3).unwrap(), "1\n2\n3\n");
+
+        let requests = server.finish();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /day/3/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=secret\r\n"));
+    }
+
+    #[test]
+    fn reports_refused_download() {
+        let server = MockServer::start(vec![(400, "Please log in.\n")]);
+        let dir = temp_dir("input");
+        let data_dir = dir.to_string_lossy().to_string();
+        let provider = InputProvider::new(&data_dir).with_base_url(&server.url).with_session("expired");
+
+        let err = provider.read(5).unwrap_err();
+        assert_eq!(err.to_string(), format!("Request to {}/day/5/input failed: HTTP 400: Please log in.", server.url));
+        assert!(!provider.is_cached(5));
+    }
+
+    #[test]
+    fn expands_directories() {
+        let dir = temp_dir("inputs");
+        for name in ["b.txt", "a.txt"] {
+            fs::write(dir.join(name), "1\n").unwrap();
+        }
+        fs::create_dir(dir.join("nested")).unwrap();
+
+        let dir = dir.to_string_lossy().to_string();
+        let paths = expand_paths(&[STDIN.to_string(), dir.clone(), "missing.txt".to_string()]).unwrap();
+
+        assert_eq!(paths, [STDIN.to_string(), format!("{dir}/a.txt"), format!("{dir}/b.txt"), "missing.txt".to_string()]);
+        assert!(matches!(expand_paths(&[format!("{dir}/nested")]), Err(Error::InvalidInput(_))));
+    }
+
+    #[test]
+    fn missing_input_without_session_is_an_io_error() {
+        let dir = temp_dir("input");
+        let provider = InputProvider::new(&dir.to_string_lossy());
+
+        assert!(matches!(provider.read(1), Err(Error::Io { .. })));
+    }
+}
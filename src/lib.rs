@@ -0,0 +1,29 @@
+//! Advent of Code 2025 solutions.
+//!
+//! Every day lives in its own `day_N` module exposing `solve_01`/`solve_02` for puzzle input
+//! text and path-based wrappers, and registers itself in [`solution`] so it can be enumerated.
+
+pub mod answers;
+pub mod bench;
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
+pub mod day_9;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod error;
+pub mod http;
+pub mod input;
+pub mod report;
+pub mod solution;
+pub mod submit;
+pub mod utils;
+
+#[cfg(test)]
+mod test_utils;
@@ -1,94 +1,411 @@
-mod day_1;
-mod day_2;
-mod day_3;
-mod day_4;
-mod day_5;
-mod day_6;
-mod day_7;
-mod day_8;
-mod day_9;
-mod day_10;
-mod day_11;
-mod day_12;
-mod utils;
-
-const DAY: &str = "day_12";
-
-fn main() {
-    match DAY {
-        "day_1" => {
-            match day_1::safe("data/day_1.txt") {
-                Ok(result) => println!("Day 1, Safe password: {}", result),
-                Err(err) => println!("Error: {}", err),
-            }
-        },
-        "day_2" => {
-            match day_2::invalid_ids("data/day_2.txt") {
-                Ok(result) => println!("Day 2, Invalid Ids sum: {}", result),
-                Err(err) => println!("Error: {}", err),
-            }
-        },
-        "day_3" => {
-            match day_3::batteries("data/day_3.txt") {
-                Ok(result) => println!("Day 3, Output joltage: {}", result),
-                Err(err) => println!("Error: {}", err),
-            }
-        },
-        "day_4" => {
-            match day_4::rolls_of_paper("data/day_4.txt") {
-                Ok(result) => println!("Day 4, Rollf os paper {}", result),
-                Err(err) => println!("Error: {}", err),
-            }
+mod cli;
+
+use std::env;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{ Duration, Instant };
+
+use aoc2025::answers::Answers;
+use aoc2025::bench::{ self, PartBench };
+use aoc2025::error::Error;
+use aoc2025::input::{ self, InputProvider };
+use aoc2025::report::{ self, RunRecord };
+use aoc2025::solution::{ self, Solution };
+use aoc2025::utils;
+use aoc2025::submit::{ self, Attempt, History, Verdict };
+use cli::{ BenchArgs, Command, FrameExport, Format, RunArgs, SubmitArgs, VerifyArgs };
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("Error: {err}\n");
+            eprintln!("{}", cli::USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match command {
+        Command::Help => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
         },
-        "day_5" => {
-            match day_5::ingredients("data/day_5.txt") {
-                Ok(result) => println!("Day 5, Fresh ingredients {}", result),
-                Err(err) => println!("Error: {}", err),
-            }
+        Command::Run(run_args) => run(&run_args),
+        Command::Verify(verify_args) => verify(&verify_args),
+        Command::Bench(bench_args) => run_bench(&bench_args),
+        Command::Submit(submit_args) => run_submit(&submit_args),
+    }
+}
+
+/// Input of `day` and where it was read from: `input` if given, `-` being stdin,
+/// otherwise the cached input under `data/`, downloaded first when missing
+fn load_input(day: u8, input: Option<&str>) -> (String, Result<String, Error>) {
+    match input {
+        Some(input::STDIN) => ("<stdin>".to_string(), input::read_input(input::STDIN)),
+        Some(path) => (path.to_string(), input::read_input(path)),
+        None => {
+            let provider = InputProvider::from_env();
+            (provider.path(day), provider.read(day))
         },
-        "day_6" => {
-            match day_6::grand_total("data/day_6.txt") {
-                Ok(result) => println!("Day 6, Grand total {}", result),
-                Err(err) => println!("Error: {}", err),
+    }
+}
+
+/// Solutions for `day`, or every registered solution when `None`
+fn select(day: Option<u8>) -> Option<Vec<&'static dyn Solution>> {
+    match day {
+        Some(day) => solution::find(day).map(|solution| vec![solution]),
+        None => Some(solution::solutions().to_vec()),
+    }
+}
+
+fn run(args: &RunArgs) -> ExitCode {
+    let started = Instant::now();
+    let mut explained = Vec::new();
+
+    let records = match args.day {
+        None => run_all(&args.parts),
+        Some(day) => {
+            let Some(solution) = solution::find(day) else {
+                eprintln!("Error: Day {day} is not solved yet");
+                return ExitCode::from(2);
+            };
+
+            let paths = match input::expand_paths(&args.inputs) {
+                Ok(paths) if paths.is_empty() => vec![None],
+                Ok(paths) => paths.into_iter().map(Some).collect(),
+                Err(err) => {
+                    eprintln!("Error: {err}");
+                    return ExitCode::FAILURE;
+                }
+            };
+            if args.frames.is_some() && paths.len() > 1 {
+                eprintln!("Error: --frames needs a single input");
+                return ExitCode::from(2);
             }
+
+            let (records, explanations) = run_day(solution, &paths, &args.parts, args.explain, args.frames.as_ref());
+            explained = explanations;
+            records
         },
-        "day_7" => {
-            match day_7::tachyon("data/day_7.txt") {
-                Ok(result) => println!("Day 7, Tachyon beam {}", result),
-                Err(err) => println!("Error: {}", err),
+    };
+
+    match args.format {
+        Format::Text if args.day.is_none() => print_table(&records, started.elapsed()),
+        Format::Text => {
+            // Name the input next to each result once there is more than one to tell apart
+            let labelled = records.first().is_some_and(|first| records.iter().any(|record| record.input != first.input))
+                || args.inputs.iter().any(|path| Path::new(path).is_dir());
+            print_lines(&records, labelled);
+            print_explanations(&explained, labelled);
+
+            if let Some(frames) = &args.frames
+                && records.iter().all(|record| record.error.is_none())
+            {
+                println!("\nFrames written to {}", frames.dir);
             }
         },
-        "day_8" => {
-            match day_8::junction_boxes("data/day_8.txt") {
-                Ok(result) => println!("Day 8, Largest curcuits {}", result),
-                Err(err) => println!("Error: {}", err),
+        Format::Json => print!("{}", report::to_json(&records)),
+        Format::Csv => print!("{}", report::to_csv(&records)),
+    }
+
+    if records.iter().any(|record| record.error.is_some()) { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+/// Solve `parts` of one day for every input in `paths`, `None` being the day's default input.
+/// With `explain`, also returns the day's breakdown of every solved part.
+/// With `frames`, also writes the day's frames of every solved part, a failure to do so failing the part.
+fn run_day(
+    solution: &dyn Solution,
+    paths: &[Option<String>],
+    parts: &[u8],
+    explain: bool,
+    frames: Option<&FrameExport>,
+) -> (Vec<RunRecord>, Vec<Explanation>) {
+    let mut records = Vec::new();
+    let mut explanations = Vec::new();
+
+    for path in paths {
+        let (path, input) = load_input(solution.day(), path.as_deref());
+        let input = input.map_err(|err| err.to_string());
+
+        for &part in parts {
+            let mut record = solve_part(solution, part, &path, &input);
+
+            if let (Some(frames), Ok(text), None) = (frames, &input, &record.error) {
+                let written = match solution.write_frames(part, text, &frames.dir, frames.format) {
+                    Some(written) => written.map_err(|err| err.in_file(&path).to_string()),
+                    None => Err(format!("Day {} has no frames to write for part {part}", solution.day())),
+                };
+                record.error = written.err();
             }
-        },
-        "day_9" => {
-            match day_9::rectangle("data/day_9.txt") {
-                Ok(result) => println!("Day 9, Largest area {}", result),
-                Err(err) => println!("Error: {}", err),
+
+            if let (true, Ok(text), None) = (explain, &input, &record.error) {
+                let text = match solution.explain(part, text) {
+                    Some(breakdown) => breakdown.map_err(|err| err.in_file(&path).to_string()),
+                    None => Err(format!("Day {} has no breakdown of its answers", solution.day())),
+                };
+                explanations.push(Explanation { day: solution.day(), part, input: path.clone(), text });
             }
-        },
-        "day_10" => {
-            match day_10::buttons("data/day_10.txt") {
-                Ok(result) => println!("Day 10, Buttons pressed {}", result),
-                Err(err) => println!("Error: {}", err),
+            records.push(record);
+        }
+    }
+
+    (records, explanations)
+}
+
+/// Breakdown of one answer, or why there is none
+struct Explanation {
+    day: u8,
+    part: u8,
+    input: String,
+    text: Result<String, String>,
+}
+
+/// Solve every registered day on the worker pool
+fn run_all(parts: &[u8]) -> Vec<RunRecord> {
+    let inputs: Vec<(String, Result<String, String>)> = solution::solutions()
+        .iter()
+        .map(|solution| {
+            let (path, input) = load_input(solution.day(), None);
+            (path, input.map_err(|err| err.to_string()))
+        })
+        .collect();
+
+    let jobs: Vec<(usize, u8)> = (0..inputs.len())
+        .flat_map(|idx| parts.iter().map(move |&part| (idx, part)))
+        .collect();
+
+    utils::parallel_map(&jobs, |&(idx, part)| {
+        let (path, input) = &inputs[idx];
+        solve_part(solution::solutions()[idx], part, path, input)
+    })
+}
+
+/// Solve and time one part, `input` holding the text or why it could not be read
+fn solve_part(solution: &dyn Solution, part: u8, path: &str, input: &Result<String, String>) -> RunRecord {
+    let start = Instant::now();
+    let result = input.as_ref().map_err(Clone::clone).and_then(|input| {
+        solution.solve(part, input).map_err(|err| err.in_file(path).to_string())
+    });
+
+    RunRecord {
+        day: solution.day(),
+        part,
+        title: solution.title(),
+        input: path.to_string(),
+        answer: result.as_ref().ok().copied(),
+        duration: start.elapsed(),
+        error: result.err(),
+    }
+}
+
+fn print_lines(records: &[RunRecord], labelled: bool) {
+    for record in records {
+        let (day, part, title) = (record.day, record.part, record.title);
+        let label = if labelled { format!(", {}", record.input) } else { String::new() };
+
+        match (&record.answer, &record.error) {
+            (_, Some(err)) => eprintln!("Day {day} ({title}), part {part}{label}, Error: {err}"),
+            (Some(answer), None) => println!("Day {day} ({title}), part {part}{label}: {answer}"),
+            (None, None) => {},
+        }
+    }
+}
+
+fn print_explanations(explanations: &[Explanation], labelled: bool) {
+    for explanation in explanations {
+        let (day, part) = (explanation.day, explanation.part);
+        let label = if labelled { format!(", {}", explanation.input) } else { String::new() };
+
+        match &explanation.text {
+            Ok(text) => println!("\nDay {day}, part {part}{label}:\n{text}"),
+            Err(err) => eprintln!("\nDay {day}, part {part}{label}, Error: {err}"),
+        }
+    }
+}
+
+/// Table of every part, a summary line and the details of each failed part.
+/// A failing part is reported in the table and does not stop the others.
+fn print_table(records: &[RunRecord], elapsed: Duration) {
+    println!("{:>3}  {:<20}  {:>4}  {:>20}  {:>12}", "Day", "Title", "Part", "Answer", "Elapsed");
+
+    let mut errors = Vec::new();
+    for record in records {
+        let answer = match (&record.answer, &record.error) {
+            (_, Some(err)) => {
+                errors.push(format!("Day {} ({}), part {}, Error: {err}", record.day, record.title, record.part));
+                "error".to_string()
+            },
+            (Some(answer), None) => answer.to_string(),
+            (None, None) => String::new(),
+        };
+
+        println!(
+            "{:>3}  {:<20}  {:>4}  {answer:>20}  {:>12}",
+            record.day,
+            record.title,
+            record.part,
+            format!("{:.3?}", record.duration),
+        );
+    }
+
+    println!("\n{} parts in {elapsed:.3?}, {} failed", records.len(), errors.len());
+
+    for err in &errors {
+        eprintln!("\n{err}");
+    }
+}
+
+fn verify(args: &VerifyArgs) -> ExitCode {
+    let Some(selected) = select(args.day) else {
+        eprintln!("Error: Day {} is not solved yet", args.day.unwrap_or_default());
+        return ExitCode::from(2);
+    };
+
+    let answers = match Answers::read(&args.answers) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut failed = false;
+
+    println!("{:>3}  {:>4}  {:>20}  {:>20}  Status", "Day", "Part", "Expected", "Actual");
+
+    for solution in selected {
+        let day = solution.day();
+        let (path, input) = load_input(day, None);
+
+        for &part in &args.parts {
+            let expected = answers.get(day, part);
+            let actual = input.as_ref()
+                .map_err(|err| err.to_string())
+                .and_then(|input| solution.solve(part, input).map_err(|err| err.in_file(&path).to_string()));
+
+            let status = match (expected, &actual) {
+                (_, Err(_)) => "error",
+                (None, Ok(_)) => "missing",
+                (Some(expected), Ok(actual)) if expected == *actual => "pass",
+                (Some(_), Ok(_)) => "FAIL",
+            };
+            failed |= matches!(status, "error" | "FAIL");
+
+            let expected = expected.map_or_else(|| "-".to_string(), |expected| expected.to_string());
+            let shown = actual.as_ref().map_or_else(|_| "-".to_string(), |actual| actual.to_string());
+            println!("{day:>3}  {part:>4}  {expected:>20}  {shown:>20}  {status}");
+
+            if let Err(err) = actual {
+                eprintln!("Day {day} ({}), part {part}, Error: {err}", solution.title());
             }
-        },
-        "day_11" => {
-            match day_11::rack("data/day_11.txt") {
-                Ok(result) => println!("Day 11, Rack paths {}", result),
-                Err(err) => println!("Error: {}", err),
+        }
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn run_bench(args: &BenchArgs) -> ExitCode {
+    let Some(selected) = select(args.day) else {
+        eprintln!("Error: Day {} is not solved yet", args.day.unwrap_or_default());
+        return ExitCode::from(2);
+    };
+
+    let mut results: Vec<PartBench> = Vec::new();
+    let mut failed = false;
+
+    println!(
+        "{:>3}  {:>4}  {:>5}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}  {:>10}",
+        "Day", "Part", "Runs", "parse min", "median", "max", "solve min", "median", "max"
+    );
+
+    for solution in selected {
+        let day = solution.day();
+        let (path, input) = load_input(day, args.input.as_deref());
+        let input = match input {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("Day {day} ({}), Error: {err}", solution.title());
+                failed = true;
+                continue;
             }
-        },
-        "day_12" => {
-            match day_12::present("data/day_12.txt") {
-                Ok(result) => println!("Day 12, Region presents fits {}", result),
-                Err(err) => println!("Error: {}", err),
+        };
+
+        for &part in &args.parts {
+            match bench::bench_part(solution, part, &input, args.runs).map_err(|err| err.in_file(&path)) {
+                Ok(result) => {
+                    println!(
+                        "{day:>3}  {part:>4}  {:>5}  {:>10.3?}  {:>10.3?}  {:>10.3?}  {:>10.3?}  {:>10.3?}  {:>10.3?}",
+                        result.runs,
+                        result.parse.min, result.parse.median, result.parse.max,
+                        result.solve.min, result.solve.median, result.solve.max,
+                    );
+                    results.push(result);
+                },
+                Err(err) => {
+                    eprintln!("Day {day} ({}), part {part}, Error: {err}", solution.title());
+                    failed = true;
+                }
             }
-        },
-        _ => unreachable!(),
+        }
+    }
+
+    if let Some(path) = &args.json
+        && let Err(err) = std::fs::write(path, bench::to_json(&results))
+    {
+        eprintln!("Error: Unable to write {path}: {err}");
+        failed = true;
     }
-    
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
+
+fn run_submit(args: &SubmitArgs) -> ExitCode {
+    let (day, part) = (args.day, args.part);
+    let Some(solution) = solution::find(day) else {
+        eprintln!("Error: Day {day} is not solved yet");
+        return ExitCode::from(2);
+    };
+
+    let (path, input) = load_input(day, args.input.as_deref());
+    let answer = match input.and_then(|input| solution.solve(part, &input)).map_err(|err| err.in_file(&path)) {
+        Ok(answer) => answer,
+        Err(err) => {
+            eprintln!("Day {day} ({}), part {part}, Error: {err}", solution.title());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut history = match History::read(&args.history) {
+        Ok(history) => history,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let time = submit::now();
+    if let Err(refusal) = history.check(day, part, answer, time) {
+        eprintln!("Day {day} ({}), part {part}: not submitting {answer}: {refusal}", solution.title());
+        return ExitCode::FAILURE;
+    }
+
+    let verdict = match submit::submit(&InputProvider::from_env(), day, part, answer) {
+        Ok(verdict) => verdict,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Day {day} ({}), part {part}: submitted {answer}: {verdict}", solution.title());
+
+    if let Err(err) = history.record(&args.history, Attempt { time, day, part, answer, verdict }) {
+        eprintln!("Error: {err}");
+        return ExitCode::FAILURE;
+    }
+
+    if verdict == Verdict::Correct { ExitCode::SUCCESS } else { ExitCode::FAILURE }
 }
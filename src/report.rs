@@ -0,0 +1,147 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Outcome of running one part of one day on one input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunRecord {
+    pub day: u8,
+    pub part: u8,
+    pub title: &'static str,
+    /// Where the input was read from
+    pub input: String,
+    pub answer: Option<u64>,
+    pub duration: Duration,
+    /// Why there is no answer
+    pub error: Option<String>,
+}
+
+/// Render records as a JSON array, durations in nanoseconds and missing values as `null`
+pub fn to_json(records: &[RunRecord]) -> String {
+    let mut json = String::from("[");
+
+    for (idx, record) in records.iter().enumerate() {
+        let separator = if idx == 0 { "" } else { "," };
+        let _ = write!(
+            json,
+            "{separator}\n  {{ \"day\": {}, \"part\": {}, \"title\": {}, \"input\": {}, \"answer\": {}, \"duration_ns\": {}, \"error\": {} }}",
+            record.day,
+            record.part,
+            json_string(record.title),
+            json_string(&record.input),
+            record.answer.map_or_else(|| "null".to_string(), |answer| answer.to_string()),
+            record.duration.as_nanos(),
+            record.error.as_deref().map_or_else(|| "null".to_string(), json_string),
+        );
+    }
+
+    if !records.is_empty() {
+        json.push('\n');
+    }
+    json.push_str("]\n");
+    json
+}
+
+/// Render records as CSV with a header row, durations in nanoseconds and missing values empty
+pub fn to_csv(records: &[RunRecord]) -> String {
+    let mut csv = String::from("day,part,title,input,answer,duration_ns,error\n");
+
+    for record in records {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            record.day,
+            record.part,
+            csv_field(record.title),
+            csv_field(&record.input),
+            record.answer.map_or_else(String::new, |answer| answer.to_string()),
+            record.duration.as_nanos(),
+            record.error.as_deref().map_or_else(String::new, csv_field),
+        );
+    }
+
+    csv
+}
+
+/// Quoted JSON string literal of `text`
+pub fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+
+    for char in text.chars() {
+        match char {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            char if char.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", char as u32);
+            },
+            char => quoted.push(char),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// `text` as a CSV field, quoted only when it has to be
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn records() -> Vec<RunRecord> {
+        vec![
+            RunRecord {
+                day: 7,
+                part: 1,
+                title: "Laboratories",
+                input: "data/day_7.txt".into(),
+                answer: Some(21),
+                duration: Duration::from_nanos(1500),
+                error: None,
+            },
+            RunRecord {
+                day: 8,
+                part: 2,
+                title: "Playground",
+                input: "a,b.txt".into(),
+                answer: None,
+                duration: Duration::ZERO,
+                error: Some("Parse error: \"x\"\n  | ^".into()),
+            },
+        ]
+    }
+
+    #[test]
+    fn renders_json() {
+        assert_eq!(to_json(&[]), "[]\n");
+        assert_eq!(to_json(&records()), r#"[
+  { "day": 7, "part": 1, "title": "Laboratories", "input": "data/day_7.txt", "answer": 21, "duration_ns": 1500, "error": null },
+  { "day": 8, "part": 2, "title": "Playground", "input": "a,b.txt", "answer": null, "duration_ns": 0, "error": "Parse error: \"x\"\n  | ^" }
+]
+"#);
+    }
+
+    #[test]
+    fn renders_csv() {
+        assert_eq!(to_csv(&records()), "\
+day,part,title,input,answer,duration_ns,error
+7,1,Laboratories,data/day_7.txt,21,1500,
+8,2,Playground,\"a,b.txt\",,0,\"Parse error: \"\"x\"\"\n  | ^\"
+");
+    }
+
+    #[test]
+    fn escapes_control_characters() {
+        assert_eq!(json_string("tab\there\u{1}"), "\"tab\\there\\u0001\"");
+    }
+}
@@ -0,0 +1,86 @@
+use crate::day_4::FrameFormat;
+use crate::error::Error;
+use crate::{ day_1, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9, day_10, day_11, day_12 };
+
+/// A single day of the calendar, solvable in two parts.
+pub trait Solution: Sync {
+    /// Day of the calendar, starting at 1
+    fn day(&self) -> u8;
+
+    /// Puzzle title as shown on the calendar
+    fn title(&self) -> &'static str;
+
+    /// Solve part one for the given puzzle input
+    fn part1(&self, input: &str) -> Result<u64, Error>;
+
+    /// Solve part two for the given puzzle input
+    fn part2(&self, input: &str) -> Result<u64, Error>;
+
+    /// Parse the puzzle input without solving it, so benchmarks can tell parsing and solving apart.
+    /// Days that parse while solving keep the default and count parsing as solve time.
+    fn parse(&self, _input: &str) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// How the answer to `part` comes about, e.g. every line's share of the total, to audit a rejected answer.
+    /// `None` for days that offer no breakdown.
+    fn explain(&self, _part: u8, _input: &str) -> Option<Result<String, Error>> {
+        None
+    }
+
+    /// Write the frames showing how the answer to `part` comes about into `dir`, one file per step.
+    /// `None` for days that have nothing to animate.
+    fn write_frames(&self, _part: u8, _input: &str, _dir: &str, _format: FrameFormat) -> Option<Result<(), Error>> {
+        None
+    }
+
+    /// Solve the given part (1 or 2)
+    fn solve(&self, part: u8, input: &str) -> Result<u64, Error> {
+        match part {
+            1 => self.part1(input),
+            2 => self.part2(input),
+            _ => Err(Error::InvalidInput(format!("Part {part} does not exist"))),
+        }
+    }
+}
+
+/// Every registered day, ordered by day number
+static SOLUTIONS: &[&dyn Solution] = &[
+    &day_1::Puzzle,
+    &day_2::Puzzle,
+    &day_3::Puzzle,
+    &day_4::Puzzle,
+    &day_5::Puzzle,
+    &day_6::Puzzle,
+    &day_7::Puzzle,
+    &day_8::Puzzle,
+    &day_9::Puzzle,
+    &day_10::Puzzle,
+    &day_11::Puzzle,
+    &day_12::Puzzle,
+];
+
+pub fn solutions() -> &'static [&'static dyn Solution] {
+    SOLUTIONS
+}
+
+pub fn find(day: u8) -> Option<&'static dyn Solution> {
+    SOLUTIONS.iter().copied().find(|solution| solution.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_by_day() {
+        let days: Vec<u8> = solutions().iter().map(|solution| solution.day()).collect();
+        assert_eq!(days, (1..=12).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn find_by_day() {
+        assert_eq!(find(7).map(|solution| solution.title()), Some("Laboratories"));
+        assert!(find(13).is_none());
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt;
+use std::fs::{ self, OpenOptions };
+use std::io::{ self, Write };
+use std::time::{ SystemTime, UNIX_EPOCH };
+
+use crate::error::Error;
+use crate::http;
+use crate::input::InputProvider;
+use crate::utils::read_file;
+
+/// The server locks out answers for a minute after a wrong one, so don't even try sooner
+pub const MIN_RETRY_SECS: u64 = 60;
+
+/// How the puzzle server judged a submitted answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the previous attempt, with the wait in seconds if the server said
+    RateLimited(Option<u64>),
+    /// The part was solved before, the answer was not checked
+    AlreadySolved,
+    /// The response did not match any known message
+    Unknown,
+}
+
+impl Verdict {
+    /// Read the verdict from the HTML of the answer page
+    pub fn parse(body: &str) -> Verdict {
+        if body.contains("That's the right answer") {
+            Verdict::Correct
+        } else if body.contains("That's not the right answer") {
+            if body.contains("too high") {
+                Verdict::TooHigh
+            } else if body.contains("too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            }
+        } else if body.contains("You gave an answer too recently") {
+            Verdict::RateLimited(parse_wait(body))
+        } else if body.contains("Did you already complete it") {
+            Verdict::AlreadySolved
+        } else {
+            Verdict::Unknown
+        }
+    }
+
+    /// True for answers the server rejected as incorrect
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Wrong => "wrong",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::RateLimited(_) => "rate-limited",
+            Verdict::AlreadySolved => "already-solved",
+            Verdict::Unknown => "unknown",
+        }
+    }
+
+    fn from_name(name: &str, wait: Option<u64>) -> Option<Verdict> {
+        Some(match name {
+            "correct" => Verdict::Correct,
+            "wrong" => Verdict::Wrong,
+            "too-high" => Verdict::TooHigh,
+            "too-low" => Verdict::TooLow,
+            "rate-limited" => Verdict::RateLimited(wait),
+            "already-solved" => Verdict::AlreadySolved,
+            "unknown" => Verdict::Unknown,
+            _ => return None,
+        })
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "That's the right answer"),
+            Verdict::Wrong => write!(f, "That's not the right answer"),
+            Verdict::TooHigh => write!(f, "That's not the right answer, it is too high"),
+            Verdict::TooLow => write!(f, "That's not the right answer, it is too low"),
+            Verdict::RateLimited(Some(wait)) => write!(f, "Answered too recently, {wait}s left to wait"),
+            Verdict::RateLimited(None) => write!(f, "Answered too recently"),
+            Verdict::AlreadySolved => write!(f, "This part is already solved"),
+            Verdict::Unknown => write!(f, "Unrecognised response"),
+        }
+    }
+}
+
+/// Seconds from "You have 1m 5s left to wait"
+fn parse_wait(body: &str) -> Option<u64> {
+    let start = body.find("You have ")? + "You have ".len();
+    let end = start + body[start..].find(" left to wait")?;
+
+    body[start..end].split_whitespace().try_fold(0, |total, part| {
+        let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let number = number.parse::<u64>().ok()?;
+        match unit {
+            "h" => Some(total + number * 3600),
+            "m" => Some(total + number * 60),
+            "s" => Some(total + number),
+            _ => None,
+        }
+    })
+}
+
+/// A submitted answer and the server's verdict
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attempt {
+    /// Unix time of the submission, in seconds
+    pub time: u64,
+    pub day: u8,
+    pub part: u8,
+    pub answer: u64,
+    pub verdict: Verdict,
+}
+
+/// Why an answer is not sent to the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refusal {
+    /// The part is already solved, with the accepted answer
+    Solved(u64),
+    /// The same answer was rejected before
+    KnownWrong(Verdict),
+    /// The answer is at or above an answer rejected as too high
+    AtOrAbove(u64),
+    /// The answer is at or below an answer rejected as too low
+    AtOrBelow(u64),
+    /// Seconds left before the server accepts another answer
+    Wait(u64),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::Solved(answer) => write!(f, "Already solved with {answer}"),
+            Refusal::KnownWrong(verdict) => write!(f, "Already submitted: {verdict}"),
+            Refusal::AtOrAbove(answer) => write!(f, "{answer} was already too high"),
+            Refusal::AtOrBelow(answer) => write!(f, "{answer} was already too low"),
+            Refusal::Wait(secs) => write!(f, "Answered too recently, wait {secs}s"),
+        }
+    }
+}
+
+/// Every submission made so far, kept as one tab-separated line per attempt:
+/// `time  day  part  answer  verdict  [wait]`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct History {
+    attempts: Vec<Attempt>,
+}
+
+impl History {
+    /// History stored at `path`, empty if the file does not exist yet
+    pub fn read(path: &str) -> Result<Self, Error> {
+        match read_file(path) {
+            Ok(text) => History::parse(&text).map_err(|err| err.in_file(path)),
+            Err(Error::Io { source, .. }) if source.kind() == io::ErrorKind::NotFound => Ok(History::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        let mut attempts = Vec::new();
+
+        for (line_idx, line) in input.lines().enumerate() {
+            let line_no = line_idx + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            if !(5..=6).contains(&fields.len()) {
+                return Err(Error::parse_at(line_no, line, line, "Expected time, day, part, answer and verdict"));
+            }
+
+            let number = |field: &str| {
+                field.parse::<u64>().map_err(|err| Error::parse_at(line_no, line, field, format!("{err}")))
+            };
+            let wait = fields.get(5).map(|field| number(field)).transpose()?;
+            let verdict = Verdict::from_name(fields[4], wait)
+                .ok_or_else(|| Error::parse_at(line_no, line, fields[4], "Unknown verdict"))?;
+            let day = u8::try_from(number(fields[1])?)
+                .map_err(|err| Error::parse_at(line_no, line, fields[1], format!("{err}")))?;
+            let part = u8::try_from(number(fields[2])?)
+                .map_err(|err| Error::parse_at(line_no, line, fields[2], format!("{err}")))?;
+
+            attempts.push(Attempt { time: number(fields[0])?, day, part, answer: number(fields[3])?, verdict });
+        }
+
+        Ok(History { attempts })
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Check `answer` against earlier attempts before sending it at unix time `now`
+    pub fn check(&self, day: u8, part: u8, answer: u64, now: u64) -> Result<(), Refusal> {
+        let same_part = || self.attempts.iter().filter(move |attempt| attempt.day == day && attempt.part == part);
+
+        if let Some(solved) = same_part().find(|attempt| attempt.verdict == Verdict::Correct) {
+            return Err(Refusal::Solved(solved.answer));
+        }
+        if let Some(known) = same_part().find(|attempt| attempt.answer == answer && attempt.verdict.is_wrong()) {
+            return Err(Refusal::KnownWrong(known.verdict));
+        }
+        if let Some(high) = same_part().filter(|attempt| attempt.verdict == Verdict::TooHigh).map(|attempt| attempt.answer).min()
+            && answer >= high
+        {
+            return Err(Refusal::AtOrAbove(high));
+        }
+        if let Some(low) = same_part().filter(|attempt| attempt.verdict == Verdict::TooLow).map(|attempt| attempt.answer).max()
+            && answer <= low
+        {
+            return Err(Refusal::AtOrBelow(low));
+        }
+
+        // The lockout applies across all puzzles
+        let ready_at = self.attempts.iter()
+            .filter_map(|attempt| match attempt.verdict {
+                Verdict::RateLimited(wait) => Some(attempt.time + wait.unwrap_or(MIN_RETRY_SECS)),
+                verdict if verdict.is_wrong() => Some(attempt.time + MIN_RETRY_SECS),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        if ready_at > now {
+            return Err(Refusal::Wait(ready_at - now));
+        }
+
+        Ok(())
+    }
+
+    /// Remember `attempt` and append it to the history file at `path`
+    pub fn record(&mut self, path: &str, attempt: Attempt) -> Result<(), Error> {
+        let io_err = |source| Error::Io { path: path.to_string(), source };
+
+        let mut line = format!(
+            "{}\t{}\t{}\t{}\t{}",
+            attempt.time, attempt.day, attempt.part, attempt.answer, attempt.verdict.name()
+        );
+        if let Verdict::RateLimited(Some(wait)) = attempt.verdict {
+            line.push_str(&format!("\t{wait}"));
+        }
+
+        if let Some(dir) = std::path::Path::new(path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(io_err)?;
+        }
+        let mut file = OpenOptions::new().create(true).append(true).open(path).map_err(io_err)?;
+        writeln!(file, "{line}").map_err(io_err)?;
+
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+/// Post `answer` for `part` of `day` to the puzzle server configured in `provider`
+pub fn submit(provider: &InputProvider, day: u8, part: u8, answer: u64) -> Result<Verdict, Error> {
+    let url = format!("{}/day/{day}/answer", provider.base_url());
+    let session = provider.session().ok_or_else(|| Error::Http {
+        url: url.clone(),
+        message: "No session token, set AOC_SESSION".into(),
+    })?;
+
+    let response = http::post_form(&url, &[("Cookie", &format!("session={session}"))], &format!("level={part}&answer={answer}"))?;
+    if !response.is_success() {
+        let reason = response.body.lines().next().unwrap_or("").trim();
+        return Err(Error::Http { url, message: format!("HTTP {}: {reason}", response.status) });
+    }
+
+    Ok(Verdict::parse(&response.body))
+}
+
+/// Current unix time in seconds
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{ temp_dir, MockServer };
+
+    fn attempt(time: u64, answer: u64, verdict: Verdict) -> Attempt {
+        Attempt { time, day: 1, part: 1, answer, verdict }
+    }
+
+    #[test]
+    fn parses_verdicts() {
+        let page = |text: &str| format!("<main>\n<article><p>{text}</p></article>\n</main>");
+
+        assert_eq!(Verdict::parse(&page("That's the right answer! You are one gold star closer.")), Verdict::Correct);
+        assert_eq!(Verdict::parse(&page("That's not the right answer; your answer is too high.")), Verdict::TooHigh);
+        assert_eq!(Verdict::parse(&page("That's not the right answer; your answer is too low.")), Verdict::TooLow);
+        assert_eq!(Verdict::parse(&page("That's not the right answer.")), Verdict::Wrong);
+        assert_eq!(
+            Verdict::parse(&page("You gave an answer too recently. You have 1m 5s left to wait.")),
+            Verdict::RateLimited(Some(65))
+        );
+        assert_eq!(Verdict::parse(&page("You don't seem to be solving the right level. Did you already complete it?")), Verdict::AlreadySolved);
+        assert_eq!(Verdict::parse("<html></html>"), Verdict::Unknown);
+    }
+
+    #[test]
+    fn refuses_known_wrong_and_out_of_bound_answers() {
+        let history = History {
+            attempts: vec![attempt(0, 500, Verdict::TooHigh), attempt(100, 100, Verdict::TooLow), attempt(200, 300, Verdict::Wrong)],
+        };
+
+        assert_eq!(history.check(1, 1, 300, 1000), Err(Refusal::KnownWrong(Verdict::Wrong)));
+        assert_eq!(history.check(1, 1, 600, 1000), Err(Refusal::AtOrAbove(500)));
+        assert_eq!(history.check(1, 1, 50, 1000), Err(Refusal::AtOrBelow(100)));
+        assert_eq!(history.check(1, 1, 250, 1000), Ok(()));
+        assert_eq!(history.check(1, 1, 250, 230), Err(Refusal::Wait(30)));
+        // Bounds only apply to the same part
+        assert_eq!(history.check(1, 2, 600, 1000), Ok(()));
+    }
+
+    #[test]
+    fn refuses_solved_parts_and_honours_server_wait() {
+        let history = History { attempts: vec![attempt(0, 42, Verdict::Correct), attempt(10, 7, Verdict::RateLimited(Some(300)))] };
+
+        assert_eq!(history.check(1, 1, 42, 1000), Err(Refusal::Solved(42)));
+        assert_eq!(history.check(2, 1, 1, 110), Err(Refusal::Wait(200)));
+        assert_eq!(history.check(2, 1, 1, 310), Ok(()));
+    }
+
+    #[test]
+    fn history_round_trips_through_file() {
+        let path = temp_dir("submit").join("history.tsv").to_string_lossy().to_string();
+        let mut history = History::read(&path).unwrap();
+        assert!(history.attempts().is_empty());
+
+        history.record(&path, attempt(1, 10, Verdict::TooLow)).unwrap();
+        history.record(&path, attempt(2, 20, Verdict::RateLimited(Some(30)))).unwrap();
+
+        assert_eq!(History::read(&path).unwrap(), history);
+        assert!(matches!(History::parse("1\t2\t3\t4\tmaybe"), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn submits_to_stub_server() {
+        let server = MockServer::start(vec![(200, "<article><p>That's not the right answer; your answer is too low.</p></article>")]);
+        let provider = InputProvider::new("data").with_base_url(&server.url).with_session("secret");
+
+        assert_eq!(submit(&provider, 4, 2, 1234).unwrap(), Verdict::TooLow);
+
+        let requests = server.finish();
+        assert!(requests[0].starts_with("POST /day/4/answer HTTP/1.1\r\n"));
+        assert!(requests[0].contains("\r\nCookie: session=secret\r\n"));
+        assert!(requests[0].ends_with("\r\n\r\nlevel=2&answer=1234"));
+    }
+}
@@ -0,0 +1,111 @@
+//! Helpers shared by the unit tests
+
+use std::io::{ Read, Write };
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::sync::{ Arc, Mutex };
+use std::thread::{ self, JoinHandle };
+use std::{ env, fs };
+
+/// Deterministic xorshift generator, good enough to drive property tests
+/// without pulling in a dependency
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck on zero
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Value within `range`, slightly biased for huge ranges which does not matter here
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let span = range.end() - range.start() + 1;
+        range.start() + self.next_u64() % span
+    }
+}
+
+/// Fresh empty directory under the system temp dir, unique per call
+pub fn temp_dir(name: &str) -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let dir = env::temp_dir().join(format!("aoc2025-{name}-{}-{id}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// HTTP server on localhost answering one connection per canned `(status, body)` response, in order,
+/// and recording the raw requests it received
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<String>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    pub fn start(responses: Vec<(u16, &'static str)>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        let handle = thread::spawn(move || {
+            for (status, body) in responses {
+                let Ok((mut stream, _)) = listener.accept() else { return };
+
+                let request = read_request(&mut stream);
+                recorded.lock().unwrap().push(request);
+
+                let response = format!("HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        MockServer { url, requests, handle: Some(handle) }
+    }
+
+    /// Requests received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Wait until every canned response has been served
+    pub fn finish(mut self) -> Vec<String> {
+        if let Some(handle) = self.handle.take() {
+            handle.join().unwrap();
+        }
+        self.requests()
+    }
+}
+
+/// Read headers and, if announced, the body of a single request
+fn read_request(stream: &mut impl Read) -> String {
+    let mut raw = Vec::new();
+    let mut byte = [0u8; 1];
+
+    while !raw.ends_with(b"\r\n\r\n") && stream.read(&mut byte).is_ok_and(|read| read == 1) {
+        raw.push(byte[0]);
+    }
+
+    let head = String::from_utf8_lossy(&raw).to_string();
+    let length = head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut body = vec![0u8; length];
+    let _ = stream.read_exact(&mut body);
+
+    head + &String::from_utf8_lossy(&body)
+}
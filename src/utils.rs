@@ -1,5 +1,86 @@
 use std::fs;
+use std::sync::Mutex;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+use std::thread;
 
-pub fn read_file(path: &str) -> String {
-    fs::read_to_string(path).expect("Unable to read file")
-}
\ No newline at end of file
+use crate::error::Error;
+
+mod grid;
+
+pub use grid::{ Grid, GridCell };
+
+pub fn read_file(path: &str) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|source| Error::Io { path: path.to_string(), source })
+}
+
+/// Ensure a character grid is ASCII only and every line is as long as the first one,
+/// so rows can be indexed by byte without bounds surprises
+pub fn check_grid(input: &str) -> Result<(), Error> {
+    let width = input.lines().next().map_or(0, str::len);
+
+    for (line_idx, line) in input.lines().enumerate() {
+        if let Some(pos) = line.find(|c: char| !c.is_ascii()) {
+            return Err(Error::parse_at(line_idx + 1, line, &line[pos..], "Grid must only contain ASCII characters"));
+        }
+
+        if line.len() != width {
+            let token = if line.len() > width { &line[width..] } else { &line[line.len()..] };
+            return Err(Error::parse_at(
+                line_idx + 1,
+                line,
+                token,
+                format!("Expected a row of {width} cells, found {}", line.len()),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply `f` to every item on a pool of scoped worker threads, one per available core.
+/// Workers take the next unclaimed item as they go, results keep the order of `items`.
+pub fn parallel_map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = thread::available_parallelism().map_or(1, |count| count.get()).min(items.len());
+    let next = AtomicUsize::new(0);
+    let results: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(idx) else { break };
+
+                    let result = f(item);
+                    *results[idx].lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(result);
+                }
+            });
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .expect("every item is claimed by exactly one worker")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_map_keeps_order() {
+        let items: Vec<u64> = (0..100).collect();
+        assert_eq!(parallel_map(&items, |n| n * n), items.iter().map(|n| n * n).collect::<Vec<u64>>());
+        assert!(parallel_map(&[] as &[u64], |n| *n).is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+use std::ops::{ Index, IndexMut };
+
+use crate::error::Error;
+use crate::utils::check_grid;
+
+/// Offsets of the edge-adjacent neighbours, as (row, column)
+const NEIGHBOURS_4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets of the edge- and corner-adjacent neighbours, as (row, column)
+const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    ( 0, -1),          ( 0, 1),
+    ( 1, -1), ( 1, 0), ( 1, 1),
+];
+
+/// Rectangular grid of cells stored row by row.
+/// Positions are `(row, column)` pairs, starting at the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Build a grid from its rows, `None` if they are not all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+
+        let height = rows.len();
+        Some(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// True when the grid has no cells
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        (row < self.height && col < self.width).then(|| &self.cells[row * self.width + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        (row < self.height && col < self.width).then(|| &mut self.cells[row * self.width + col])
+    }
+
+    /// Cells of `row`, panics if it is out of bounds
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// Edge-adjacent positions of `(row, col)` that lie within the grid
+    pub fn neighbours4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        self.neighbours(row, col, &NEIGHBOURS_4)
+    }
+
+    /// Edge- and corner-adjacent positions of `(row, col)` that lie within the grid
+    pub fn neighbours8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        self.neighbours(row, col, &NEIGHBOURS_8)
+    }
+
+    fn neighbours(
+        &self,
+        row: usize,
+        col: usize,
+        offsets: &'static [(isize, isize)],
+    ) -> impl Iterator<Item = (usize, usize)> + use<T> {
+        let (width, height) = (self.width, self.height);
+
+        offsets.iter().filter_map(move |&(d_row, d_col)| {
+            let row = row.checked_add_signed(d_row)?;
+            let col = col.checked_add_signed(d_col)?;
+            (row < height && col < width).then_some((row, col))
+        })
+    }
+
+    /// First position, row by row, whose cell matches `predicate`
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        let idx = self.cells.iter().position(predicate)?;
+        Some((idx / self.width, idx % self.width))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// First position, row by row, holding `value`
+    pub fn find(&self, value: &T) -> Option<(usize, usize)> {
+        self.position(|cell| cell == value)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: usize, height: usize, value: T) -> Self {
+        Grid { width, height, cells: vec![value; width * height] }
+    }
+
+    /// Grid with rows and columns swapped
+    pub fn transpose(&self) -> Self {
+        self.remap(self.height, self.width, |row, col| (col, row))
+    }
+
+    /// Grid rotated by 90 degrees clockwise
+    pub fn rotate_cw(&self) -> Self {
+        self.remap(self.height, self.width, |row, col| (self.height - 1 - col, row))
+    }
+
+    /// Grid mirrored left to right
+    pub fn flip_horizontal(&self) -> Self {
+        self.remap(self.width, self.height, |row, col| (row, self.width - 1 - col))
+    }
+
+    /// Build a `width` x `height` grid whose cell at `(row, col)` is taken from `source(row, col)`
+    fn remap(&self, width: usize, height: usize, source: impl Fn(usize, usize) -> (usize, usize)) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| self[source(row, col)].clone())
+            .collect();
+
+        Grid { width, height, cells }
+    }
+}
+
+impl Grid<u8> {
+    /// Parse an ASCII grid whose lines are all as wide as the first one
+    pub fn parse(input: &str) -> Result<Self, Error> {
+        check_grid(input)?;
+
+        let width = input.lines().next().map_or(0, str::len);
+        let cells: Vec<u8> = input.lines().flat_map(str::bytes).collect();
+
+        Ok(Grid { width, height: input.lines().count(), cells })
+    }
+
+    /// Parse an ASCII grid of ragged lines, padding short lines with `fill` up to the widest one
+    pub fn parse_padded(input: &str, fill: u8) -> Result<Self, Error> {
+        let width = input.lines().map(str::len).max().unwrap_or(0);
+        let mut cells = Vec::new();
+
+        for (line_idx, line) in input.lines().enumerate() {
+            if let Some(pos) = line.find(|c: char| !c.is_ascii()) {
+                return Err(Error::parse_at(line_idx + 1, line, &line[pos..], "Grid must only contain ASCII characters"));
+            }
+
+            cells.extend(line.bytes());
+            cells.resize((line_idx + 1) * width, fill);
+        }
+
+        Ok(Grid { width, height: input.lines().count(), cells })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(col < self.width, "column {col} out of bounds for width {}", self.width);
+        &self.cells[row * self.width + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(col < self.width, "column {col} out of bounds for width {}", self.width);
+        &mut self.cells[row * self.width + col]
+    }
+}
+
+/// Cells that can be drawn as a single character
+pub trait GridCell {
+    fn to_char(&self) -> char;
+}
+
+impl GridCell for u8 {
+    fn to_char(&self) -> char {
+        *self as char
+    }
+}
+
+impl GridCell for char {
+    fn to_char(&self) -> char {
+        *self
+    }
+}
+
+impl GridCell for bool {
+    fn to_char(&self) -> char {
+        if *self { '#' } else { '.' }
+    }
+}
+
+/// Draws one line per row, without a trailing newline
+impl<T: GridCell> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell.to_char())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_prints() {
+        let grid = Grid::parse("ab.\n.S#\n").unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 2)], b'#');
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.find(&b'S'), Some((1, 1)));
+        assert_eq!(grid.to_string(), "ab.\n.S#");
+        assert!(matches!(Grid::parse("ab\nc\n"), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn pads_ragged_lines() {
+        let grid = Grid::parse_padded("1 2\n3\n", b' ').unwrap();
+        assert_eq!(grid.to_string(), "1 2\n3  ");
+    }
+
+    #[test]
+    fn neighbours_stay_in_bounds() {
+        let grid = Grid::filled(3, 2, false);
+
+        assert_eq!(grid.neighbours4(0, 0).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours8(0, 0).collect::<Vec<_>>(), [(0, 1), (1, 0), (1, 1)]);
+        assert_eq!(grid.neighbours8(1, 1).count(), 5);
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = Grid::parse("abc\ndef\n").unwrap();
+
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.flip_horizontal().to_string(), "cba\nfed");
+        assert_eq!(grid.rotate_cw().rotate_cw().rotate_cw().rotate_cw(), grid);
+    }
+}